@@ -0,0 +1,291 @@
+use std::fmt;
+
+use crate::enigma::{
+    Enigma,
+    error::EnigmaError,
+    preset::{LATIN_ALPHABET, Model},
+};
+
+const LETTERS: usize = LATIN_ALPHABET.len();
+const UNKNOWN: u8 = u8::MAX;
+
+fn to_letters(text: &str) -> Result<Vec<u8>, EnigmaError> {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' => Ok(b - b'A'),
+            _ => Err(EnigmaError::InvalidCrib(
+                "crib and ciphertext must be letters A-Z",
+            )),
+        })
+        .collect()
+}
+
+fn to_char(letter: u8) -> char {
+    (b'A' + letter) as char
+}
+
+// Ребро меню: на позиции `offset` шифртекста буква открытого текста `plain`
+// перешла в букву `cipher`. Буквы хранятся номерами в алфавите A-Z
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MenuEdge {
+    pub plain: u8,
+    pub cipher: u8,
+    pub offset: usize,
+}
+
+pub struct Menu {
+    edges: Vec<MenuEdge>,
+    // Для каждой буквы: соседняя буква и номер ребра
+    adjacency: Vec<Vec<(u8, usize)>>,
+    test_letter: u8,
+}
+
+impl Menu {
+    // Криб - предполагаемый открытый текст, начинающийся с позиции
+    // `position` шифртекста
+    pub fn new(ciphertext: &str, crib: &str, position: usize) -> Result<Self, EnigmaError> {
+        let ciphertext = to_letters(ciphertext)?;
+        let crib = to_letters(crib)?;
+
+        if crib.is_empty() {
+            return Err(EnigmaError::InvalidCrib("crib is empty"));
+        }
+        if position + crib.len() > ciphertext.len() {
+            return Err(EnigmaError::InvalidCrib("crib doesn't fit into ciphertext"));
+        }
+
+        let mut edges = Vec::with_capacity(crib.len());
+        let mut adjacency = vec![Vec::new(); LETTERS];
+
+        for (i, &plain) in crib.iter().enumerate() {
+            let cipher = ciphertext[position + i];
+            // Энигма никогда не шифрует букву в саму себя
+            if plain == cipher {
+                return Err(EnigmaError::InvalidCrib(
+                    "letter can't be encrypted to itself",
+                ));
+            }
+
+            adjacency[plain as usize].push((cipher, edges.len()));
+            adjacency[cipher as usize].push((plain, edges.len()));
+            edges.push(MenuEdge {
+                plain,
+                cipher,
+                offset: position + i,
+            });
+        }
+
+        let test_letter = (0..LETTERS)
+            .max_by_key(|&letter| adjacency[letter].len())
+            .unwrap_or(0) as u8;
+
+        Ok(Menu {
+            edges,
+            adjacency,
+            test_letter,
+        })
+    }
+
+    pub fn edges(&self) -> &[MenuEdge] {
+        &self.edges
+    }
+
+    // Буква с наибольшим числом связей, с нее начинается проверка гипотез
+    pub fn test_letter(&self) -> char {
+        to_char(self.test_letter)
+    }
+
+    // Количество независимых циклов меню: чем их больше, тем меньше
+    // ложных остановок
+    pub fn loops(&self) -> usize {
+        let mut parent: Vec<usize> = (0..LETTERS).collect();
+        fn root(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                parent[x] = parent[parent[x]];
+                x = parent[x];
+            }
+            x
+        }
+
+        let mut loops = 0;
+        for edge in &self.edges {
+            let a = root(&mut parent, edge.plain as usize);
+            let b = root(&mut parent, edge.cipher as usize);
+            if a == b {
+                loops += 1;
+            } else {
+                parent[a] = b;
+            }
+        }
+
+        loops
+    }
+
+    // Следствия гипотезы "буква `test_letter` соединена с `guess`" по всем
+    // ребрам меню. Диагональная доска добавляет симметричное соединение.
+    // Противоречие - одна буква соединена с двумя разными
+    fn closure(&self, scramblers: &[[u8; LETTERS]], guess: u8) -> Option<[u8; LETTERS]> {
+        let mut steckers = [UNKNOWN; LETTERS];
+        let mut stack = Vec::with_capacity(LETTERS);
+
+        let connect = |steckers: &mut [u8; LETTERS], a: u8, b: u8, stack: &mut Vec<u8>| {
+            if steckers[a as usize] == b {
+                return true;
+            }
+            if steckers[a as usize] != UNKNOWN || steckers[b as usize] != UNKNOWN {
+                return false;
+            }
+
+            steckers[a as usize] = b;
+            steckers[b as usize] = a;
+            stack.push(a);
+            if a != b {
+                stack.push(b);
+            }
+            true
+        };
+
+        connect(&mut steckers, self.test_letter, guess, &mut stack);
+
+        while let Some(letter) = stack.pop() {
+            let plugged = steckers[letter as usize];
+
+            for &(other, edge) in &self.adjacency[letter as usize] {
+                let wired = scramblers[edge][plugged as usize];
+                if !connect(&mut steckers, other, wired, &mut stack) {
+                    return None;
+                }
+            }
+        }
+
+        Some(steckers)
+    }
+}
+
+// Остановка бомбы: порядок роторов и начальные позиции слева направо
+// при кольцевых установках AAA и выведенные из меню соединения коммутатора
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BombeStop {
+    pub rotors: Vec<String>,
+    pub positions: String,
+    pub steckers: Vec<(char, char)>,
+}
+
+impl fmt::Display for BombeStop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steckers: Vec<String> = self
+            .steckers
+            .iter()
+            .map(|(a, b)| format!("{}{}", a, b))
+            .collect();
+
+        write!(
+            f,
+            "{} {}: {}",
+            self.rotors.join(" "),
+            self.positions,
+            steckers.join(" ")
+        )
+    }
+}
+
+pub struct Bombe {
+    model: Model,
+    reflector: String,
+}
+
+impl Bombe {
+    pub fn new(model: Model, reflector: &str) -> Result<Self, EnigmaError> {
+        if model.rotors_cnt() != 3 {
+            return Err(EnigmaError::InvalidPreset(
+                "bombe supports only three-rotor models",
+            ));
+        }
+        if !model
+            .reflectors()
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(reflector))
+        {
+            return Err(EnigmaError::InvalidPreset(
+                "reflector is not available for Enigma model",
+            ));
+        }
+
+        Ok(Bombe {
+            model,
+            reflector: reflector.to_string(),
+        })
+    }
+
+    // Все порядки роторов модели слева направо
+    pub fn rotor_orders(&self) -> Vec<[&'static str; 3]> {
+        let rotors = self.model.rotors();
+        let mut orders = Vec::new();
+
+        for left in rotors {
+            for middle in rotors.iter().filter(|r| *r != left) {
+                for right in rotors.iter().filter(|r| *r != left && *r != middle) {
+                    orders.push([*left, *middle, *right]);
+                }
+            }
+        }
+
+        orders
+    }
+
+    // Перебирает порядки роторов и все начальные позиции. Кольцевые установки
+    // считаются равными AAA: как и у настоящей бомбы, поворот среднего ротора
+    // внутри криба может помешать найти настройку
+    pub fn run(
+        &self,
+        menu: &Menu,
+        rotor_orders: &[[&str; 3]],
+    ) -> Result<Vec<BombeStop>, EnigmaError> {
+        let mut stops = Vec::new();
+        let mut scramblers = vec![[0; LETTERS]; menu.edges.len()];
+
+        for order in rotor_orders {
+            let mut enigma: Enigma<u8> =
+                Enigma::from_preset(self.model, &self.reflector, order, "AAA", "AAA", "")?;
+
+            for start in 0..LETTERS.pow(3) {
+                let start = [
+                    start % LETTERS,
+                    start / LETTERS % LETTERS,
+                    start / LETTERS.pow(2),
+                ];
+                for (rotor, &position) in enigma.rotors.iter_mut().zip(&start) {
+                    rotor.set_position(position);
+                }
+
+                // Подстановка шифратора без коммутатора для каждого ребра меню
+                let mut steps = 0;
+                for (scrambler, edge) in scramblers.iter_mut().zip(&menu.edges) {
+                    enigma.advance(edge.offset + 1 - steps);
+                    steps = edge.offset + 1;
+
+                    for (letter, wired) in scrambler.iter_mut().enumerate() {
+                        *wired = enigma.substitute(letter) as u8;
+                    }
+                }
+
+                for guess in 0..LETTERS as u8 {
+                    if let Some(steckers) = menu.closure(&scramblers, guess) {
+                        stops.push(BombeStop {
+                            rotors: order.iter().map(|r| r.to_string()).collect(),
+                            positions: start.iter().rev().map(|&p| to_char(p as u8)).collect(),
+                            steckers: (0..LETTERS as u8)
+                                .filter(|&a| {
+                                    steckers[a as usize] != UNKNOWN && a <= steckers[a as usize]
+                                })
+                                .map(|a| (to_char(a), to_char(steckers[a as usize])))
+                                .collect(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(stops)
+    }
+}
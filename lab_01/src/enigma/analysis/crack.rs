@@ -0,0 +1,413 @@
+use std::thread;
+
+use crate::enigma::{
+    Enigma,
+    cfg::{self, EnigmaConfig},
+    error::EnigmaError,
+    preset::Model,
+};
+
+use super::language::{NgramModel, index_of_coincidence};
+
+// Сколько лучших по индексу совпадений установок уточняется подбором коммутатора
+const DEFAULT_CANDIDATES: usize = 5;
+const DEFAULT_MAX_PLUGS: usize = 10;
+// Больше установок без коммутатора не перебрать за разумное время
+const MAX_SEARCH_SETTINGS: u64 = 100_000_000;
+
+enum Source {
+    Preset { model: Model, reflector: String },
+    Config(EnigmaConfig<char>),
+}
+
+// Найденная установка: роторы слева направо, полная конфигурация
+// (кольца в первом символе алфавита) и расшифрованный текст
+#[derive(Clone, Debug)]
+pub struct CrackResult {
+    pub rotors: Vec<String>,
+    pub config: EnigmaConfig<char>,
+    pub plaintext: Vec<char>,
+    pub score: f64,
+}
+
+struct Candidate {
+    ioc: f64,
+    order: Vec<usize>,
+    start: Vec<usize>,
+}
+
+pub struct Cracker<'a> {
+    source: Source,
+    language: &'a NgramModel,
+    alphabet: Vec<char>,
+    candidates: usize,
+    max_plugs: usize,
+}
+
+impl<'a> Cracker<'a> {
+    // Перебираются роторы исторической модели, рефлектор известен
+    pub fn from_preset(
+        model: Model,
+        reflector: &str,
+        language: &'a NgramModel,
+    ) -> Result<Self, EnigmaError> {
+        if model.rotors_cnt() != 3 {
+            return Err(EnigmaError::InvalidPreset(
+                "attack supports only three-rotor models",
+            ));
+        }
+        if !model
+            .reflectors()
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(reflector))
+        {
+            return Err(EnigmaError::InvalidPreset(
+                "reflector is not available for Enigma model",
+            ));
+        }
+
+        Cracker::new(
+            Source::Preset {
+                model,
+                reflector: reflector.to_string(),
+            },
+            ('A'..='Z').collect(),
+            language,
+        )
+    }
+
+    // Проводка роторов и рефлектора известна из конфигурации, неизвестны
+    // порядок роторов, их позиции и коммутатор
+    pub fn from_config(
+        config: &EnigmaConfig<char>,
+        language: &'a NgramModel,
+    ) -> Result<Self, EnigmaError> {
+        config.validate()?;
+
+        Cracker::new(
+            Source::Config(config.clone()),
+            cfg::sorted_alphabet(&config.alphabet)?,
+            language,
+        )
+    }
+
+    fn new(
+        source: Source,
+        alphabet: Vec<char>,
+        language: &'a NgramModel,
+    ) -> Result<Self, EnigmaError> {
+        if language.alphabet() != alphabet {
+            return Err(EnigmaError::LanguageMismatch);
+        }
+
+        Ok(Cracker {
+            source,
+            language,
+            alphabet,
+            candidates: DEFAULT_CANDIDATES,
+            max_plugs: DEFAULT_MAX_PLUGS,
+        })
+    }
+
+    pub fn set_candidates(&mut self, candidates: usize) {
+        self.candidates = candidates.max(1);
+    }
+
+    pub fn set_max_plugs(&mut self, max_plugs: usize) {
+        self.max_plugs = max_plugs;
+    }
+
+    // Названия доступных роторов, для конфигурации - номера роторов в ней
+    pub fn rotor_names(&self) -> Vec<String> {
+        match &self.source {
+            Source::Preset { model, .. } => model.rotors().iter().map(|r| r.to_string()).collect(),
+            Source::Config(config) => (0..config.rotors.len())
+                .map(|i| format!("#{}", i))
+                .collect(),
+        }
+    }
+
+    // Количество доступных роторов и мест для них в машине
+    fn slots(&self) -> (usize, usize) {
+        match &self.source {
+            Source::Preset { model, .. } => (model.rotors().len(), model.rotors_cnt()),
+            Source::Config(config) => (config.rotors.len(), config.rotors.len()),
+        }
+    }
+
+    // Все порядки роторов слева направо номерами в `rotor_names`
+    pub fn rotor_orders(&self) -> Vec<Vec<usize>> {
+        let (available, slots) = self.slots();
+
+        let mut orders = vec![Vec::new()];
+        for _ in 0..slots {
+            let mut next = Vec::new();
+            for order in &orders {
+                for i in (0..available).filter(|i| !order.contains(i)) {
+                    let mut order = order.clone();
+                    order.push(i);
+                    next.push(order);
+                }
+            }
+            orders = next;
+        }
+
+        orders
+    }
+
+    // Машина с заданным порядком роторов, позициями и кольцами в первом
+    // символе алфавита и без коммутатора
+    fn machine(&self, order: &[usize], start: &[usize]) -> Result<Enigma<char>, EnigmaError> {
+        let first = self.alphabet[0];
+
+        let mut config = match &self.source {
+            Source::Preset { model, reflector } => {
+                let names = self.rotor_names();
+                let names: Vec<&str> = order.iter().map(|&i| names[i].as_str()).collect();
+                let settings: String = names.iter().map(|_| first).collect();
+                Enigma::from_preset(*model, reflector, &names, &settings, &settings, "")?
+                    .get_config()
+            }
+            Source::Config(config) => EnigmaConfig {
+                alphabet: config.alphabet.clone(),
+                commutator: None,
+                entry: config.entry.clone(),
+                reflector: config.reflector.clone(),
+                rotors: order
+                    .iter()
+                    .rev()
+                    .map(|&i| config.rotors[i].clone())
+                    .collect(),
+                mechanics: config.mechanics,
+            },
+        };
+
+        for (rotor, &position) in config.rotors.iter_mut().zip(start) {
+            rotor.ring = first;
+            rotor.position = self.alphabet[position];
+        }
+
+        Enigma::from_config(&config)
+    }
+
+    // Начальные позиции всех роторов, None при переполнении
+    fn positions_cnt(&self, slots: usize) -> Option<u64> {
+        (self.alphabet.len() as u64).checked_pow(u32::try_from(slots).ok()?)
+    }
+
+    fn check_search_size(settings: Option<u64>) -> Result<(), EnigmaError> {
+        match settings {
+            Some(settings) if settings <= MAX_SEARCH_SETTINGS => Ok(()),
+            _ => Err(EnigmaError::InvalidSearch("too many settings to search")),
+        }
+    }
+
+    // Размер перебора проверяется до перечисления порядков роторов:
+    // для конфигурации с многими роторами их список сам не помещается в память
+    pub fn crack(&self, ciphertext: &[char]) -> Result<CrackResult, EnigmaError> {
+        let (available, slots) = self.slots();
+        let orders_cnt =
+            (available - slots + 1..=available).try_fold(1u64, |cnt, i| cnt.checked_mul(i as u64));
+        Self::check_search_size(
+            orders_cnt.and_then(|cnt| cnt.checked_mul(self.positions_cnt(slots)?)),
+        )?;
+
+        self.crack_orders(ciphertext, &self.rotor_orders())
+    }
+
+    // Сначала для каждого порядка роторов перебираются все начальные позиции
+    // без коммутатора и отбираются установки с наибольшим индексом
+    // совпадений, затем для них восхождением подбирается коммутатор:
+    // сначала по биграммам, потом по триграммам
+    pub fn crack_orders(
+        &self,
+        ciphertext: &[char],
+        rotor_orders: &[Vec<usize>],
+    ) -> Result<CrackResult, EnigmaError> {
+        let text: Vec<usize> = ciphertext
+            .iter()
+            .enumerate()
+            .map(|(i, symb)| {
+                self.alphabet
+                    .binary_search(symb)
+                    .map_err(|_| EnigmaError::SymbolNotInAlphabet(i))
+            })
+            .collect::<Result<_, _>>()?;
+
+        if rotor_orders.is_empty() {
+            return Err(EnigmaError::InvalidSearch("no rotor orders to search"));
+        }
+        Self::check_search_size(rotor_orders.iter().try_fold(0u64, |cnt, order| {
+            cnt.checked_add(self.positions_cnt(order.len())?)
+        }))?;
+
+        let n = self.alphabet.len();
+
+        // Порядки роторов перебираются независимо, поэтому делятся между потоками
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = rotor_orders.len().div_ceil(threads).max(1);
+        let mut candidates = Vec::new();
+        thread::scope(|s| {
+            let workers: Vec<_> = rotor_orders
+                .chunks(chunk_size)
+                .map(|orders| s.spawn(|| self.search(&text, orders)))
+                .collect();
+
+            for worker in workers {
+                candidates.extend(worker.join().expect("Search thread panicked")?);
+            }
+            Ok::<_, EnigmaError>(())
+        })?;
+        candidates.sort_by(|a, b| b.ioc.total_cmp(&a.ioc));
+        candidates.truncate(self.candidates);
+
+        let mut best: Option<(f64, Enigma<char>, &Candidate)> = None;
+        for candidate in &candidates {
+            let mut enigma = self.machine(&candidate.order, &vec![0; candidate.order.len()])?;
+
+            self.climb(&mut enigma, &candidate.start, &text, |t| {
+                self.language.bigram_score(t)
+            });
+            let score = self.climb(&mut enigma, &candidate.start, &text, |t| {
+                self.language.trigram_score(t)
+            });
+
+            if best.as_ref().is_none_or(|(s, _, _)| score > *s) {
+                best = Some((score, enigma, candidate));
+            }
+        }
+
+        let (score, enigma, candidate) =
+            best.ok_or(EnigmaError::InvalidSearch("no rotor orders to search"))?;
+
+        let mut config = self
+            .machine(&candidate.order, &candidate.start)?
+            .get_config();
+        let pairs: Vec<char> = (0..n)
+            .filter(|&a| enigma.plugs[a] > a)
+            .flat_map(|a| [self.alphabet[a], self.alphabet[enigma.plugs[a]]])
+            .collect();
+        if !pairs.is_empty() {
+            config.commutator = Some(pairs);
+        }
+
+        let plaintext = Enigma::from_config(&config)?.decrypt(ciphertext)?;
+        let names = self.rotor_names();
+
+        Ok(CrackResult {
+            rotors: candidate.order.iter().map(|&i| names[i].clone()).collect(),
+            config,
+            plaintext,
+            score,
+        })
+    }
+
+    // Лучшие по индексу совпадений установки без коммутатора
+    fn search(
+        &self,
+        text: &[usize],
+        rotor_orders: &[Vec<usize>],
+    ) -> Result<Vec<Candidate>, EnigmaError> {
+        let n = self.alphabet.len();
+        let mut candidates: Vec<Candidate> = Vec::with_capacity(self.candidates + 1);
+        let mut counts = vec![0; n];
+
+        for order in rotor_orders {
+            let mut enigma = self.machine(order, &vec![0; order.len()])?;
+            let slots = enigma.rotors.len();
+
+            for start in 0..n.pow(slots as u32) {
+                let start: Vec<usize> = (0..slots).map(|i| start / n.pow(i as u32) % n).collect();
+                place(&mut enigma, &start);
+
+                counts.fill(0);
+                for &symb in text {
+                    counts[enigma.press(symb)] += 1;
+                }
+
+                let ioc = index_of_coincidence(&counts);
+                if candidates.len() < self.candidates || ioc > candidates[candidates.len() - 1].ioc
+                {
+                    candidates.push(Candidate {
+                        ioc,
+                        order: order.clone(),
+                        start,
+                    });
+                    candidates.sort_by(|a, b| b.ioc.total_cmp(&a.ioc));
+                    candidates.truncate(self.candidates);
+                }
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    // Жадно меняет соединения коммутатора, пока оценка текста растет
+    fn climb(
+        &self,
+        enigma: &mut Enigma<char>,
+        start: &[usize],
+        text: &[usize],
+        score_of: impl Fn(&[usize]) -> f64,
+    ) -> f64 {
+        let n = self.alphabet.len();
+        let mut plaintext = vec![0; text.len()];
+
+        let decrypt = |enigma: &mut Enigma<char>, plaintext: &mut Vec<usize>| {
+            place(enigma, start);
+            for (symb, plain) in text.iter().zip(plaintext.iter_mut()) {
+                *plain = enigma.press(*symb);
+            }
+            score_of(plaintext)
+        };
+
+        let mut best = decrypt(enigma, &mut plaintext);
+        let mut improved = true;
+
+        while improved {
+            improved = false;
+
+            for a in 0..n {
+                for b in a + 1..n {
+                    let saved = enigma.plugs.clone();
+                    let plugs_cnt = (0..n).filter(|&i| enigma.plugs[i] > i).count();
+
+                    // Соединенная пара разъединяется, иначе a и b освобождаются
+                    // от прежних соединений и соединяются между собой
+                    let connected = enigma.plugs[a] == b;
+                    for x in [a, b] {
+                        let y = enigma.plugs[x];
+                        enigma.plugs[x] = x;
+                        enigma.plugs[y] = y;
+                    }
+                    if !connected {
+                        enigma.plugs[a] = b;
+                        enigma.plugs[b] = a;
+                    }
+
+                    let new_cnt = (0..n).filter(|&i| enigma.plugs[i] > i).count();
+                    if new_cnt > self.max_plugs && new_cnt > plugs_cnt {
+                        enigma.plugs = saved;
+                        continue;
+                    }
+
+                    let score = decrypt(enigma, &mut plaintext);
+                    if score > best {
+                        best = score;
+                        improved = true;
+                    } else {
+                        enigma.plugs = saved;
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
+
+fn place(enigma: &mut Enigma<char>, start: &[usize]) {
+    for (rotor, &position) in enigma.rotors.iter_mut().zip(start) {
+        rotor.set_position(position);
+    }
+}
@@ -0,0 +1,114 @@
+const ENGLISH_CORPUS: &str = include_str!("corpus/english.txt");
+const RUSSIAN_CORPUS: &str = include_str!("corpus/russian.txt");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Russian,
+}
+
+impl Language {
+    pub fn alphabet(&self) -> Vec<char> {
+        match self {
+            Language::English => ('A'..='Z').collect(),
+            Language::Russian => ('А'..='Я').collect(),
+        }
+    }
+
+    // Приведение символа текста к алфавиту языка: заглавные буквы,
+    // Ё заменяется на Е, как принято в шифровках
+    pub fn normalize(&self, symbol: char) -> char {
+        let symbol = symbol.to_uppercase().next().unwrap_or(symbol);
+
+        match (self, symbol) {
+            (Language::Russian, 'Ё') => 'Е',
+            _ => symbol,
+        }
+    }
+
+    pub fn model(&self) -> NgramModel {
+        let corpus = match self {
+            Language::English => ENGLISH_CORPUS,
+            Language::Russian => RUSSIAN_CORPUS,
+        };
+        let corpus: String = corpus.chars().map(|c| self.normalize(c)).collect();
+
+        NgramModel::from_corpus(&self.alphabet(), &corpus)
+    }
+}
+
+// Логарифмы частот биграмм и триграмм. Пробелы и знаки препинания
+// выбрасываются, как и при шифровании
+pub struct NgramModel {
+    alphabet: Vec<char>,
+    bigrams: Vec<f64>,
+    trigrams: Vec<f64>,
+}
+
+impl NgramModel {
+    pub fn from_corpus(alphabet: &[char], corpus: &str) -> Self {
+        let mut alphabet = alphabet.to_vec();
+        alphabet.sort();
+        let n = alphabet.len();
+
+        let text: Vec<usize> = corpus
+            .chars()
+            .filter_map(|c| alphabet.binary_search(&c).ok())
+            .collect();
+
+        let mut bigrams = vec![0.0; n * n];
+        let mut trigrams = vec![0.0; n * n * n];
+        for w in text.windows(2) {
+            bigrams[w[0] * n + w[1]] += 1.0;
+        }
+        for w in text.windows(3) {
+            trigrams[(w[0] * n + w[1]) * n + w[2]] += 1.0;
+        }
+
+        // Сглаживание, чтобы не встретившиеся в корпусе сочетания
+        // не обнуляли оценку
+        let to_log = |counts: &mut Vec<f64>| {
+            let total: f64 = counts.iter().sum::<f64>() + 0.5 * counts.len() as f64;
+            for count in counts.iter_mut() {
+                *count = ((*count + 0.5) / total).ln();
+            }
+        };
+        to_log(&mut bigrams);
+        to_log(&mut trigrams);
+
+        NgramModel {
+            alphabet,
+            bigrams,
+            trigrams,
+        }
+    }
+
+    pub fn alphabet(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    // Текст задается номерами символов в отсортированном алфавите
+    pub fn bigram_score(&self, text: &[usize]) -> f64 {
+        let n = self.alphabet.len();
+        text.windows(2).map(|w| self.bigrams[w[0] * n + w[1]]).sum()
+    }
+
+    pub fn trigram_score(&self, text: &[usize]) -> f64 {
+        let n = self.alphabet.len();
+        text.windows(3)
+            .map(|w| self.trigrams[(w[0] * n + w[1]) * n + w[2]])
+            .sum()
+    }
+}
+
+// Вероятность того, что два случайно выбранных символа текста совпадают:
+// у осмысленного текста она заметно выше, чем у случайного
+pub fn index_of_coincidence(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+        return 0.0;
+    }
+
+    let pairs: usize = counts.iter().map(|&c| c * c.saturating_sub(1)).sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
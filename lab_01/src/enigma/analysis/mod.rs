@@ -0,0 +1,3 @@
+pub mod bombe;
+pub mod crack;
+pub mod language;
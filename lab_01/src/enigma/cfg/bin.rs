@@ -1,79 +1,379 @@
-use super::ConfigSerializer;
+use super::{ConfigSerializer, ConfigSymbol, EnigmaConfig, EnigmaState, Mechanics, RotorConfig};
 use std::{
     fs::File,
-    io::{Error, ErrorKind, Read, Write},
+    io::{BufReader, Cursor, Error, ErrorKind, Read, Write},
 };
 
-const BYTE_CNT: usize = 256;
-
 pub struct BinConfigSerializer;
 
-impl ConfigSerializer<u8> for BinConfigSerializer {
-    fn save_configs(
-        file: &mut File,
-        commutator_config: Option<&[u8]>,
-        reflector_config: &[u8],
-        rotors_configs: &[Vec<u8>],
-    ) -> Result<(), Error> {
-        for rotor_config in rotors_configs {
-            if rotor_config.len() != BYTE_CNT {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "Rotor config length mismatch",
-                ));
-            }
+// Заголовок: сигнатура, версия формата, размер символа, длина алфавита и флаги
+// компонентов. За данными следует CRC-32 заголовка и данных.
+// Файлы без заголовка - формат версии 0.1: только байты, без вырезов роторов.
+// Вырезы, кольца и позиции роторов записываются начиная с первой версии,
+// во второй добавлены входное колесо и неподвижные роторы,
+// в третьей - флаг механики версии 0.1
+pub const MAGIC: [u8; 4] = *b"ENGM";
+pub const FORMAT_VERSION: u8 = 3;
+
+const HEADER_SIZE: usize = 11;
+const LEGACY_ALPHABET_LEN: usize = 256;
+const CHECKSUM_SIZE: usize = 4;
+
+const FLAG_COMMUTATOR: u8 = 1;
+const FLAG_POSITIONS: u8 = 1 << 1;
+const FLAG_ENTRY: u8 = 1 << 2;
+const FLAG_LEGACY: u8 = 1 << 3;
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn write_symbols<T: ConfigSymbol>(buf: &mut Vec<u8>, symbols: &[T]) -> Result<(), Error> {
+    let len = u32::try_from(symbols.len())
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Config component is too long"))?;
+
+    buf.extend_from_slice(&len.to_le_bytes());
+    for symbol in symbols {
+        symbol.to_bin(buf);
+    }
+
+    Ok(())
+}
+
+fn read_symbol<T: ConfigSymbol>(reader: &mut impl Read) -> Result<T, Error> {
+    let mut symbol_buf = vec![0; T::BIN_SIZE];
+    reader.read_exact(&mut symbol_buf)?;
+
+    T::from_bin(&symbol_buf).ok_or(Error::new(ErrorKind::InvalidData, "Invalid config symbol"))
+}
+
+fn read_symbols<T: ConfigSymbol>(reader: &mut impl Read) -> Result<Vec<T>, Error> {
+    let mut len_buf = [0; 4];
+    reader.read_exact(&mut len_buf)?;
+    let len = u32::from_le_bytes(len_buf);
+
+    (0..len).map(|_| read_symbol(reader)).collect()
+}
+
+fn write_config<T: ConfigSymbol>(buf: &mut Vec<u8>, config: &EnigmaConfig<T>) -> Result<(), Error> {
+    for rotor_config in &config.rotors {
+        if rotor_config.wiring.len() != config.alphabet.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Rotor config length mismatch",
+            ));
         }
-    
-        match commutator_config {
-            Some(cfg) => {
-                file.write_all(&[1u8])?;
-                file.write_all(cfg)?;
-            }
-            None => {
-                file.write_all(&[0u8])?;
-            }
+    }
+
+    write_symbols(buf, &config.alphabet)?;
+
+    match &config.commutator {
+        Some(cfg) => {
+            buf.push(1u8);
+            write_symbols(buf, cfg)?;
         }
-    
-        file.write_all(reflector_config)?;
-    
-        let rotors_cnt = rotors_configs.len();
-        if rotors_cnt > 255 {
-            return Err(Error::new(ErrorKind::InvalidInput, "Too many rotors"));
+        None => {
+            buf.push(0u8);
+        }
+    }
+
+    match &config.entry {
+        Some(cfg) => {
+            buf.push(1u8);
+            write_symbols(buf, cfg)?;
         }
-        file.write_all(&[rotors_cnt as u8])?;
-    
-        for rotor_config in rotors_configs {
-            file.write_all(rotor_config)?;
+        None => {
+            buf.push(0u8);
         }
-    
-        Ok(())
-    }
-    
-    fn get_configs(file: &mut File) -> Result<(Option<Vec<u8>>, Vec<u8>, Vec<Vec<u8>>), Error> {
-        let mut num_buf = [0; 1];
-        file.read_exact(&mut num_buf)?;
-        let is_with_commutator = num_buf[0];
-    
-        let mut reflector_config = vec![0; BYTE_CNT as usize];
-        let commutator_config = if is_with_commutator != 0 {
-            file.read_exact(&mut reflector_config)?;
-            Some(reflector_config.clone())
-        } else {
-            None
-        };
-    
-        file.read_exact(&mut reflector_config)?;
-    
-        file.read_exact(&mut num_buf)?;
-        let rotors_cnt = num_buf[0];
-    
-        let mut rotors_configs = Vec::with_capacity(rotors_cnt as usize);
-        for _ in 0..rotors_cnt {
-            let mut irotor_config = vec![0; BYTE_CNT as usize];
-            file.read_exact(&mut irotor_config)?;
-            rotors_configs.push(irotor_config);
+    }
+
+    write_symbols(buf, &config.reflector)?;
+
+    let rotors_cnt = config.rotors.len();
+    if rotors_cnt > 255 {
+        return Err(Error::new(ErrorKind::InvalidInput, "Too many rotors"));
+    }
+    buf.push(rotors_cnt as u8);
+
+    for rotor_config in &config.rotors {
+        write_symbols(buf, &rotor_config.wiring)?;
+        write_symbols(buf, &rotor_config.notches)?;
+        rotor_config.ring.to_bin(buf);
+        rotor_config.position.to_bin(buf);
+        buf.push(rotor_config.stationary as u8);
+    }
+
+    Ok(())
+}
+
+// В первой версии нет входного колеса и неподвижных роторов
+fn read_config<T: ConfigSymbol>(
+    reader: &mut impl Read,
+    version: u8,
+) -> Result<EnigmaConfig<T>, Error> {
+    let alphabet = read_symbols(reader)?;
+
+    let mut num_buf = [0; 1];
+    reader.read_exact(&mut num_buf)?;
+    let is_with_commutator = num_buf[0];
+
+    let commutator = if is_with_commutator != 0 {
+        Some(read_symbols(reader)?)
+    } else {
+        None
+    };
+
+    let mut entry = None;
+    if version >= 2 {
+        reader.read_exact(&mut num_buf)?;
+        if num_buf[0] != 0 {
+            entry = Some(read_symbols(reader)?);
+        }
+    }
+
+    let reflector = read_symbols(reader)?;
+
+    reader.read_exact(&mut num_buf)?;
+    let rotors_cnt = num_buf[0];
+
+    let mut rotors = Vec::with_capacity(rotors_cnt as usize);
+    for _ in 0..rotors_cnt {
+        let wiring = read_symbols(reader)?;
+        let notches = read_symbols(reader)?;
+        let ring = read_symbol(reader)?;
+        let position = read_symbol(reader)?;
+
+        let mut stationary = false;
+        if version >= 2 {
+            reader.read_exact(&mut num_buf)?;
+            stationary = num_buf[0] != 0;
+        }
+
+        rotors.push(RotorConfig {
+            wiring,
+            notches,
+            ring,
+            position,
+            stationary,
+        });
+    }
+
+    Ok(EnigmaConfig {
+        alphabet,
+        commutator,
+        entry,
+        reflector,
+        rotors,
+        mechanics: Mechanics::Historical,
+    })
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+// Формат версии 0.1: флаг коммутатора, коммутатор, рефлектор, количество роторов
+// и их проводки, по байту на символ. Алфавит - все 256 байт, вырезов и колец нет,
+// роторы стартуют с нулевой позиции и вращаются по механике версии 0.1
+fn read_legacy_config<T: ConfigSymbol>(data: &[u8]) -> Result<EnigmaConfig<T>, Error> {
+    if T::BIN_SIZE != 1 {
+        return Err(invalid("Config file of version 0.1 stores only bytes"));
+    }
+
+    let symbols = |bytes: &[u8]| -> Result<Vec<T>, Error> {
+        bytes
+            .iter()
+            .map(|&b| T::from_bin(&[b]).ok_or(invalid("Invalid config symbol")))
+            .collect()
+    };
+    let zero = || T::from_bin(&[0]).ok_or(invalid("Invalid config symbol"));
+
+    let mut reader = Cursor::new(data);
+    let mut read = |len: usize| -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0; len];
+        reader
+            .read_exact(&mut buf)
+            .map_err(|_| invalid("Config file is truncated"))?;
+        Ok(buf)
+    };
+
+    let commutator = if read(1)?[0] != 0 {
+        Some(symbols(&read(LEGACY_ALPHABET_LEN)?)?)
+    } else {
+        None
+    };
+    let reflector = symbols(&read(LEGACY_ALPHABET_LEN)?)?;
+
+    let rotors_cnt = read(1)?[0];
+    let mut rotors = Vec::with_capacity(rotors_cnt as usize);
+    for _ in 0..rotors_cnt {
+        rotors.push(RotorConfig {
+            wiring: symbols(&read(LEGACY_ALPHABET_LEN)?)?,
+            notches: vec![zero()?],
+            ring: zero()?,
+            position: zero()?,
+            stationary: false,
+        });
+    }
+
+    if reader.position() as usize != data.len() {
+        return Err(invalid("Config file of version 0.1 has trailing data"));
+    }
+
+    Ok(EnigmaConfig {
+        alphabet: symbols(&(0..=255).collect::<Vec<u8>>())?,
+        commutator,
+        entry: None,
+        reflector,
+        rotors,
+        mechanics: Mechanics::Legacy,
+    })
+}
+
+fn write_file<T: ConfigSymbol>(
+    file: &mut File,
+    config: &EnigmaConfig<T>,
+    positions: Option<&[T]>,
+) -> Result<(), Error> {
+    let alphabet_len = u32::try_from(config.alphabet.len())
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Config component is too long"))?;
+
+    let mut flags = 0;
+    if config.commutator.is_some() {
+        flags |= FLAG_COMMUTATOR;
+    }
+    if positions.is_some() {
+        flags |= FLAG_POSITIONS;
+    }
+    if config.entry.is_some() {
+        flags |= FLAG_ENTRY;
+    }
+    if config.mechanics == Mechanics::Legacy {
+        flags |= FLAG_LEGACY;
+    }
+
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&MAGIC);
+    buf.push(FORMAT_VERSION);
+    buf.push(T::BIN_SIZE as u8);
+    buf.extend_from_slice(&alphabet_len.to_le_bytes());
+    buf.push(flags);
+
+    write_config(&mut buf, config)?;
+    if let Some(positions) = positions {
+        write_symbols(&mut buf, positions)?;
+    }
+
+    let checksum = crc32(&buf);
+    buf.extend_from_slice(&checksum.to_le_bytes());
+
+    file.write_all(&buf)
+}
+
+// Возвращает конфигурацию и позиции, если они записаны в файле
+fn read_file<T: ConfigSymbol>(
+    file: &mut File,
+    positions: bool,
+) -> Result<(EnigmaConfig<T>, Option<Vec<T>>), Error> {
+    let mut data = Vec::new();
+    BufReader::new(file).read_to_end(&mut data)?;
+
+    // Промежуточная раскладка с вырезами без заголовка не выпускалась,
+    // поэтому файл без сигнатуры может быть только файлом версии 0.1.
+    // Состояния машины версия 0.1 не сохраняла
+    if !data.starts_with(&MAGIC) {
+        if positions {
+            return Err(invalid("Config file of version 0.1 has no rotor positions"));
         }
-    
-        Ok((commutator_config, reflector_config, rotors_configs))
+        return read_legacy_config(&data).map(|config| (config, None));
+    }
+
+    if data.len() < HEADER_SIZE + CHECKSUM_SIZE {
+        return Err(invalid("Config file is truncated"));
+    }
+    let (header, rest) = data.split_at(HEADER_SIZE);
+    let (body, checksum) = rest.split_at(rest.len() - CHECKSUM_SIZE);
+
+    let version = header[4];
+    if !(1..=FORMAT_VERSION).contains(&version) {
+        return Err(invalid("Unsupported config format version"));
+    }
+    if header[5] as usize != T::BIN_SIZE {
+        return Err(invalid("Config symbol size mismatch"));
+    }
+    if crc32(&data[..data.len() - CHECKSUM_SIZE]).to_le_bytes() != checksum {
+        return Err(invalid(
+            "Config checksum mismatch, file is corrupted or truncated",
+        ));
+    }
+
+    let alphabet_len = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+    let flags = header[10];
+    if flags & FLAG_POSITIONS != 0 && !positions {
+        return Err(invalid("File contains machine state, not config"));
+    }
+    if flags & FLAG_POSITIONS == 0 && positions {
+        return Err(invalid("File contains config without rotor positions"));
+    }
+
+    if flags & FLAG_LEGACY != 0 && version < 3 {
+        return Err(invalid("Unsupported config flags"));
+    }
+
+    let mut reader = Cursor::new(body);
+    let mut config = read_config(&mut reader, version)?;
+    if flags & FLAG_LEGACY != 0 {
+        config.mechanics = Mechanics::Legacy;
+    }
+    let positions = if positions {
+        Some(read_symbols(&mut reader)?)
+    } else {
+        None
+    };
+
+    if config.alphabet.len() != alphabet_len as usize
+        || config.commutator.is_some() != (flags & FLAG_COMMUTATOR != 0)
+        || config.entry.is_some() != (flags & FLAG_ENTRY != 0)
+        || reader.position() as usize != body.len()
+    {
+        return Err(invalid("Config body does not match header"));
+    }
+
+    Ok((config, positions))
+}
+
+// Состояние записывается как конфигурация, за которой идут текущие позиции
+impl<T: ConfigSymbol> ConfigSerializer<T> for BinConfigSerializer {
+    fn save_configs(file: &mut File, config: &EnigmaConfig<T>) -> Result<(), Error> {
+        write_file(file, config, None)
+    }
+
+    fn get_configs(file: &mut File) -> Result<EnigmaConfig<T>, Error> {
+        read_file(file, false).map(|(config, _)| config)
+    }
+
+    fn save_state(file: &mut File, state: &EnigmaState<T>) -> Result<(), Error> {
+        write_file(file, &state.config, Some(&state.positions))
+    }
+
+    fn get_state(file: &mut File) -> Result<EnigmaState<T>, Error> {
+        let (config, positions) = read_file(file, true)?;
+
+        Ok(EnigmaState {
+            config,
+            positions: positions.unwrap_or_default(),
+        })
     }
 }
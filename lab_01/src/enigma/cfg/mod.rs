@@ -1,15 +1,187 @@
 pub mod bin;
+pub mod text;
 
 pub use bin::BinConfigSerializer;
+pub use text::TomlConfigSerializer;
+
 use std::{fs::File, io::Error};
 
+use super::{Mechanics, Plugboard, error::{Component, EnigmaError}};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RotorConfig<T> {
+    pub wiring: Vec<T>,
+    pub notches: Vec<T>,
+    pub ring: T,
+    pub position: T,
+    // Неподвижный ротор (статор) не вращается при нажатии клавиш,
+    // но его позицию можно установить вручную
+    pub stationary: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnigmaConfig<T> {
+    pub alphabet: Vec<T>,
+    pub commutator: Option<Vec<T>>,
+    // Проводка входного колеса между коммутатором и роторами,
+    // без него контакты соединены напрямую
+    pub entry: Option<Vec<T>>,
+    pub reflector: Vec<T>,
+    pub rotors: Vec<RotorConfig<T>>,
+    pub mechanics: Mechanics,
+}
+
+// Состояние машины посреди шифрования: конфигурация с начальными позициями
+// и текущие позиции роторов в порядке `Enigma::positions`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnigmaState<T> {
+    pub config: EnigmaConfig<T>,
+    pub positions: Vec<T>,
+}
+
+pub(crate) fn sorted_alphabet<T: Clone + Ord>(alphabet: &[T]) -> Result<Vec<T>, EnigmaError> {
+    if alphabet.is_empty() {
+        return Err(EnigmaError::EmptyAlphabet);
+    }
+
+    let mut sorted = alphabet.to_vec();
+    sorted.sort();
+    if sorted.windows(2).any(|w| w[0] == w[1]) {
+        return Err(EnigmaError::DuplicateSymbol(Component::Alphabet));
+    }
+
+    Ok(sorted)
+}
+
+fn check_permutation<T: Clone + Ord>(
+    sorted_alphabet: &[T],
+    wiring: &[T],
+    component: Component,
+) -> Result<(), EnigmaError> {
+    if wiring.len() != sorted_alphabet.len() {
+        return Err(EnigmaError::LengthMismatch {
+            component,
+            expected: sorted_alphabet.len(),
+            actual: wiring.len(),
+        });
+    }
+
+    let mut sorted = wiring.to_vec();
+    sorted.sort();
+    if sorted.windows(2).any(|w| w[0] == w[1]) {
+        return Err(EnigmaError::DuplicateSymbol(component));
+    }
+    if sorted != sorted_alphabet {
+        return Err(EnigmaError::InvalidPermutation(component));
+    }
+
+    Ok(())
+}
+
+impl<T: Clone + Ord> EnigmaConfig<T> {
+    // Проверка всей конфигурации до создания машины, чтобы ошибки
+    // не проявлялись посреди шифрования
+    pub fn validate(&self) -> Result<(), EnigmaError> {
+        let alphabet = sorted_alphabet(&self.alphabet)?;
+
+        check_permutation(&alphabet, &self.reflector, Component::Reflector).map_err(|e| match e {
+            EnigmaError::LengthMismatch { .. } => e,
+            _ => EnigmaError::InvalidReflector,
+        })?;
+
+        if let Some(cfg) = &self.commutator {
+            Plugboard::from_config(cfg)?;
+            if cfg.iter().any(|symb| alphabet.binary_search(symb).is_err()) {
+                return Err(EnigmaError::InvalidPlugboard("symbol not in alphabet"));
+            }
+        }
+
+        if let Some(entry) = &self.entry {
+            check_permutation(&alphabet, entry, Component::EntryWheel)?;
+        }
+
+        for (i, rotor) in self.rotors.iter().enumerate() {
+            check_permutation(&alphabet, &rotor.wiring, Component::Rotor(i))?;
+
+            let in_alphabet = |symb: &T| alphabet.binary_search(symb).is_ok();
+            if !rotor.notches.iter().all(in_alphabet)
+                || !in_alphabet(&rotor.ring)
+                || !in_alphabet(&rotor.position)
+            {
+                return Err(EnigmaError::InvalidRotorSetting(i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Представление символа алфавита в конфигурационных файлах
+pub trait ConfigSymbol: Sized {
+    const BIN_SIZE: usize;
+
+    fn to_bin(&self, buf: &mut Vec<u8>);
+    fn from_bin(bytes: &[u8]) -> Option<Self>;
+
+    fn to_text(symbols: &[Self]) -> String;
+    fn from_text(text: &str) -> Option<Vec<Self>>;
+}
+
+impl ConfigSymbol for u8 {
+    const BIN_SIZE: usize = 1;
+
+    fn to_bin(&self, buf: &mut Vec<u8>) {
+        buf.push(*self);
+    }
+
+    fn from_bin(bytes: &[u8]) -> Option<Self> {
+        bytes.first().copied()
+    }
+
+    // Байты записываются шестнадцатеричными цифрами
+    fn to_text(symbols: &[Self]) -> String {
+        symbols.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn from_text(text: &str) -> Option<Vec<Self>> {
+        let digits: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if !digits.len().is_multiple_of(2) {
+            return None;
+        }
+
+        digits
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(&pair.iter().collect::<String>(), 16).ok())
+            .collect()
+    }
+}
+
+impl ConfigSymbol for char {
+    const BIN_SIZE: usize = 4;
+
+    fn to_bin(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&(*self as u32).to_le_bytes());
+    }
+
+    fn from_bin(bytes: &[u8]) -> Option<Self> {
+        char::from_u32(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    fn to_text(symbols: &[Self]) -> String {
+        symbols.iter().collect()
+    }
+
+    fn from_text(text: &str) -> Option<Vec<Self>> {
+        Some(text.chars().collect())
+    }
+}
+
 pub trait ConfigSerializer<T> {
-    fn save_configs(
-        file: &mut File,
-        commutator_config: Option<&[T]>,
-        reflector_config: &[T],
-        rotors_configs: &[Vec<T>],
-    ) -> Result<(), Error>;
-
-    fn get_configs(file: &mut File) -> Result<(Option<Vec<T>>, Vec<T>, Vec<Vec<T>>), Error>;
-}
\ No newline at end of file
+    fn save_configs(file: &mut File, config: &EnigmaConfig<T>) -> Result<(), Error>;
+
+    fn get_configs(file: &mut File) -> Result<EnigmaConfig<T>, Error>;
+
+    fn save_state(file: &mut File, state: &EnigmaState<T>) -> Result<(), Error>;
+
+    fn get_state(file: &mut File) -> Result<EnigmaState<T>, Error>;
+}
@@ -0,0 +1,163 @@
+use super::{ConfigSerializer, ConfigSymbol, EnigmaConfig, EnigmaState, Mechanics, RotorConfig};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{Error, ErrorKind, Read, Write},
+};
+
+// Каждый компонент записывается отдельной строкой, чтобы изменения
+// ключей были видны в diff
+#[derive(Serialize, Deserialize)]
+struct TextConfig {
+    alphabet: String,
+    commutator: Option<Vec<String>>,
+    // Необязательные поля, чтобы читались файлы, записанные до их появления
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    reflector: String,
+    rotors: Vec<TextRotorConfig>,
+    // Механика версии 0.1
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    legacy: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TextRotorConfig {
+    wiring: String,
+    notches: String,
+    ring: String,
+    position: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    stationary: bool,
+}
+
+// Текущие позиции записываются перед конфигурацией
+#[derive(Serialize, Deserialize)]
+struct TextState {
+    positions: String,
+    #[serde(flatten)]
+    config: TextConfig,
+}
+
+pub struct TomlConfigSerializer;
+
+fn decode<T: ConfigSymbol>(text: &str) -> Result<Vec<T>, Error> {
+    T::from_text(text).ok_or(Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid config symbols '{}'", text),
+    ))
+}
+
+fn decode_symbol<T: ConfigSymbol>(text: &str) -> Result<T, Error> {
+    let mut symbols = decode(text)?;
+    if symbols.len() != 1 {
+        return Err(Error::new(ErrorKind::InvalidData, "Expected single symbol"));
+    }
+
+    Ok(symbols.remove(0))
+}
+
+fn encode<T: ConfigSymbol>(config: &EnigmaConfig<T>) -> TextConfig {
+    TextConfig {
+        alphabet: T::to_text(&config.alphabet),
+        commutator: config
+            .commutator
+            .as_ref()
+            .map(|cfg| cfg.chunks(2).map(T::to_text).collect()),
+        entry: config.entry.as_deref().map(T::to_text),
+        reflector: T::to_text(&config.reflector),
+        rotors: config
+            .rotors
+            .iter()
+            .map(|rotor_config| TextRotorConfig {
+                wiring: T::to_text(&rotor_config.wiring),
+                notches: T::to_text(&rotor_config.notches),
+                ring: T::to_text(std::slice::from_ref(&rotor_config.ring)),
+                position: T::to_text(std::slice::from_ref(&rotor_config.position)),
+                stationary: rotor_config.stationary,
+            })
+            .collect(),
+        legacy: config.mechanics == Mechanics::Legacy,
+    }
+}
+
+fn decode_config<T: ConfigSymbol + Clone>(text_config: TextConfig) -> Result<EnigmaConfig<T>, Error> {
+    let commutator = match text_config.commutator {
+        Some(pairs) => Some(
+            pairs
+                .iter()
+                .map(|pair| decode(pair))
+                .collect::<Result<Vec<Vec<T>>, _>>()?
+                .concat(),
+        ),
+        None => None,
+    };
+
+    let rotors = text_config
+        .rotors
+        .iter()
+        .map(|rotor_config| {
+            Ok(RotorConfig {
+                wiring: decode(&rotor_config.wiring)?,
+                notches: decode(&rotor_config.notches)?,
+                ring: decode_symbol(&rotor_config.ring)?,
+                position: decode_symbol(&rotor_config.position)?,
+                stationary: rotor_config.stationary,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(EnigmaConfig {
+        alphabet: decode(&text_config.alphabet)?,
+        commutator,
+        entry: text_config.entry.as_deref().map(decode).transpose()?,
+        reflector: decode(&text_config.reflector)?,
+        rotors,
+        mechanics: if text_config.legacy {
+            Mechanics::Legacy
+        } else {
+            Mechanics::Historical
+        },
+    })
+}
+
+fn write_toml(file: &mut File, value: &impl Serialize) -> Result<(), Error> {
+    let text = toml::to_string(value).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    file.write_all(text.as_bytes())
+}
+
+fn read_toml<V: for<'de> Deserialize<'de>>(file: &mut File) -> Result<V, Error> {
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+
+    toml::from_str(&text).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+impl<T: ConfigSymbol + Clone> ConfigSerializer<T> for TomlConfigSerializer {
+    fn save_configs(file: &mut File, config: &EnigmaConfig<T>) -> Result<(), Error> {
+        write_toml(file, &encode(config))
+    }
+
+    fn get_configs(file: &mut File) -> Result<EnigmaConfig<T>, Error> {
+        decode_config(read_toml(file)?)
+    }
+
+    fn save_state(file: &mut File, state: &EnigmaState<T>) -> Result<(), Error> {
+        write_toml(
+            file,
+            &TextState {
+                positions: T::to_text(&state.positions),
+                config: encode(&state.config),
+            },
+        )
+    }
+
+    fn get_state(file: &mut File) -> Result<EnigmaState<T>, Error> {
+        let text_state: TextState = read_toml(file)?;
+
+        Ok(EnigmaState {
+            positions: decode(&text_state.positions)?,
+            config: decode_config(text_state.config)?,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+// Входное колесо (ETW) - неподвижная перестановка между коммутатором
+// и роторами. Сигнал проходит его дважды: к роторам и обратно
+#[derive(Clone)]
+pub struct EntryWheel<T> {
+    alphabet: Vec<T>,
+    wiring: Vec<usize>,
+    inverse: Vec<usize>,
+}
+
+impl<T: Clone + Ord> EntryWheel<T> {
+    // Конфигурация должна быть проверена `EnigmaConfig::validate`
+    pub fn from_config(config: &[T]) -> Self {
+        let mut sorted_alphabet = config.to_vec();
+        sorted_alphabet.sort();
+
+        let wiring: Vec<usize> = config
+            .iter()
+            .map(|symb| {
+                sorted_alphabet
+                    .binary_search(symb)
+                    .expect("Entry wheel symbol not in alphabet")
+            })
+            .collect();
+
+        let mut inverse = vec![0; wiring.len()];
+        for (i, &j) in wiring.iter().enumerate() {
+            inverse[j] = i;
+        }
+
+        EntryWheel {
+            alphabet: sorted_alphabet,
+            wiring,
+            inverse,
+        }
+    }
+
+    pub fn get_config(&self) -> Vec<T> {
+        self.wiring
+            .iter()
+            .map(|&i| self.alphabet[i].clone())
+            .collect()
+    }
+
+    pub fn forward(&self, index: usize) -> usize {
+        self.wiring[index]
+    }
+
+    pub fn backward(&self, index: usize) -> usize {
+        self.inverse[index]
+    }
+}
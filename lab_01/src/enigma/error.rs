@@ -0,0 +1,103 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Component {
+    Alphabet,
+    Commutator,
+    EntryWheel,
+    Reflector,
+    Rotor(usize),
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Component::Alphabet => write!(f, "alphabet"),
+            Component::Commutator => write!(f, "commutator"),
+            Component::EntryWheel => write!(f, "entry wheel"),
+            Component::Reflector => write!(f, "reflector"),
+            Component::Rotor(i) => write!(f, "rotor #{}", i),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnigmaError {
+    EmptyAlphabet,
+    DuplicateSymbol(Component),
+    LengthMismatch {
+        component: Component,
+        expected: usize,
+        actual: usize,
+    },
+    InvalidPermutation(Component),
+    InvalidReflector,
+    InvalidPlugboard(&'static str),
+    InvalidRotorSetting(usize),
+    PositionsCount {
+        expected: usize,
+        actual: usize,
+    },
+    MissingIndicator,
+    InvalidPreset(&'static str),
+    InvalidCrib(&'static str),
+    InvalidSearch(&'static str),
+    LanguageMismatch,
+    InvalidKeySheet(&'static str),
+    SymbolNotInAlphabet(usize),
+    UnsavedStepping,
+}
+
+impl fmt::Display for EnigmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnigmaError::EmptyAlphabet => write!(f, "Alphabet is empty"),
+            EnigmaError::DuplicateSymbol(component) => {
+                write!(f, "Duplicate symbol in {}", component)
+            }
+            EnigmaError::LengthMismatch {
+                component,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Length of {} is {}, expected {}",
+                component, actual, expected
+            ),
+            EnigmaError::InvalidPermutation(component) => {
+                write!(
+                    f,
+                    "Wiring of {} is not a permutation of alphabet",
+                    component
+                )
+            }
+            EnigmaError::InvalidReflector => write!(f, "Reflector must pair symbols of alphabet"),
+            EnigmaError::InvalidPlugboard(reason) => write!(f, "Invalid plugboard: {}", reason),
+            EnigmaError::InvalidRotorSetting(i) => {
+                write!(f, "Notch, ring or position of rotor #{} not in alphabet", i)
+            }
+            EnigmaError::PositionsCount { expected, actual } => {
+                write!(f, "Got {} rotor positions, expected {}", actual, expected)
+            }
+            EnigmaError::MissingIndicator => write!(f, "Message is shorter than indicator"),
+            EnigmaError::InvalidPreset(reason) => write!(f, "Invalid preset: {}", reason),
+            EnigmaError::InvalidCrib(reason) => write!(f, "Invalid crib: {}", reason),
+            EnigmaError::InvalidSearch(reason) => write!(f, "Invalid search: {}", reason),
+            EnigmaError::InvalidKeySheet(reason) => write!(f, "Invalid key sheet: {}", reason),
+            EnigmaError::LanguageMismatch => {
+                write!(f, "Alphabet of language differs from alphabet of machine")
+            }
+            EnigmaError::SymbolNotInAlphabet(pos) => {
+                write!(f, "Pos: {}; Symbol not in alphabet", pos)
+            }
+            EnigmaError::UnsavedStepping => {
+                write!(
+                    f,
+                    "Stepping mechanism is not saved in config, state can't be restored"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnigmaError {}
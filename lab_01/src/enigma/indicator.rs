@@ -0,0 +1,74 @@
+use rand::{Rng, rng, seq::IndexedRandom};
+
+use super::{Enigma, error::EnigmaError, symbol::Symbol};
+
+// Процедура ключа сообщения: начальные позиции роторов для каждого
+// сообщения выбираются случайно, шифруются суточной установкой и передаются
+// индикатором в начале сообщения. Позиции перечисляются в порядке `positions`
+impl<T: Symbol> Enigma<T> {
+    pub fn random_message_key(&self) -> Vec<T> {
+        self.random_message_key_with_rng(&mut rng())
+    }
+
+    pub fn random_message_key_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<T> {
+        self.rotors
+            .iter()
+            .map(|_| {
+                self.alphabet
+                    .choose(rng)
+                    .expect("Alphabet is empty")
+                    .clone()
+            })
+            .collect()
+    }
+
+    // Шифрует ключ сообщения с начальных позиций и ставит роторы
+    // в позиции ключа
+    pub fn encrypt_indicator(&mut self, message_key: &[T]) -> Result<Vec<T>, EnigmaError> {
+        self.reset();
+        let indicator = self.encrypt(message_key)?;
+        self.set_positions(message_key)?;
+
+        Ok(indicator)
+    }
+
+    // Восстанавливает ключ сообщения из индикатора и ставит роторы
+    // в его позиции
+    pub fn decrypt_indicator(&mut self, indicator: &[T]) -> Result<Vec<T>, EnigmaError> {
+        self.reset();
+        let message_key = self.decrypt(indicator)?;
+        self.set_positions(&message_key)?;
+
+        Ok(message_key)
+    }
+
+    pub fn encrypt_with_indicator(
+        &mut self,
+        buf: &[T],
+        message_key: &[T],
+    ) -> Result<Vec<T>, EnigmaError> {
+        let mut ebuf = self.encrypt_indicator(message_key)?;
+        let offset = ebuf.len();
+
+        ebuf.extend(self.encrypt(buf).map_err(|e| shift_error(e, offset))?);
+        Ok(ebuf)
+    }
+
+    pub fn decrypt_with_indicator(&mut self, buf: &[T]) -> Result<Vec<T>, EnigmaError> {
+        let len = self.rotors.len();
+        if buf.len() < len {
+            return Err(EnigmaError::MissingIndicator);
+        }
+
+        self.decrypt_indicator(&buf[..len])?;
+        self.decrypt(&buf[len..]).map_err(|e| shift_error(e, len))
+    }
+}
+
+// Позиция ошибки считается от начала сообщения вместе с индикатором
+fn shift_error(err: EnigmaError, offset: usize) -> EnigmaError {
+    match err {
+        EnigmaError::SymbolNotInAlphabet(i) => EnigmaError::SymbolNotInAlphabet(offset + i),
+        err => err,
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{Error, ErrorKind, Read, Write},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rand::{
+    Rng, SeedableRng,
+    seq::{IndexedRandom, SliceRandom},
+};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Enigma,
+    error::EnigmaError,
+    preset::{GREEK_ROTORS, LATIN_ALPHABET, Model},
+    symbol::Symbol,
+};
+
+// Количество соединений коммутатора, как в ключевых таблицах вермахта
+const PLUGBOARD_PAIRS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        Date::from_days((secs / 86400) as i64)
+    }
+
+    // Перевод в дни от 1970-01-01 и обратно по пролептическому
+    // григорианскому календарю
+    fn to_days(self) -> i64 {
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = if month <= 2 { self.year - 1 } else { self.year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
+
+    fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Date {
+            year,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    pub fn add_days(self, days: i64) -> Self {
+        Date::from_days(self.to_days() + days)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = EnigmaError;
+
+    // Формат YYYY-MM-DD
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = EnigmaError::InvalidKeySheet("date must be in format YYYY-MM-DD");
+
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let [year, month, day] = parts[..] else {
+            return Err(invalid);
+        };
+
+        let date = Date {
+            year: year.parse().map_err(|_| invalid.clone())?,
+            month: month.parse().map_err(|_| invalid.clone())?,
+            day: day.parse().map_err(|_| invalid.clone())?,
+        };
+
+        // Несуществующие даты вроде 02-30 не переживают перевод в дни и обратно
+        if Date::from_days(date.to_days()) != date {
+            return Err(invalid);
+        }
+
+        Ok(date)
+    }
+}
+
+// Суточная установка: роторы, кольца и позиции перечисляются слева направо
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DailyKey {
+    pub date: Date,
+    pub reflector: String,
+    pub rotors: Vec<String>,
+    pub rings: String,
+    pub positions: String,
+    pub plugboard: String,
+}
+
+impl DailyKey {
+    fn generate(model: Model, date: Date, rng: &mut ChaCha20Rng) -> Self {
+        let letters = LATIN_ALPHABET.as_bytes();
+        let random_letters = |rng: &mut ChaCha20Rng, cnt: usize| -> String {
+            (0..cnt)
+                .map(|_| letters[rng.random_range(0..letters.len())] as char)
+                .collect()
+        };
+
+        let mut rotors: Vec<String> = Vec::with_capacity(model.rotors_cnt());
+        if model == Model::M4 {
+            let greek = GREEK_ROTORS.choose(rng).expect("No greek rotors");
+            rotors.push(greek.name.to_string());
+        }
+        let mut available = model.rotors().to_vec();
+        available.shuffle(rng);
+        rotors.extend(available[..3].iter().map(|r| r.to_string()));
+
+        let mut plugs = letters.to_vec();
+        plugs.shuffle(rng);
+        let plugboard: Vec<String> = plugs[..PLUGBOARD_PAIRS * 2]
+            .chunks(2)
+            .map(|pair| String::from_utf8_lossy(pair).into_owned())
+            .collect();
+
+        DailyKey {
+            date,
+            reflector: model
+                .reflectors()
+                .choose(rng)
+                .expect("No reflectors")
+                .to_string(),
+            rings: random_letters(rng, rotors.len()),
+            positions: random_letters(rng, rotors.len()),
+            rotors,
+            plugboard: plugboard.join(" "),
+        }
+    }
+
+    pub fn enigma<T: Symbol + From<u8>>(&self, model: Model) -> Result<Enigma<T>, EnigmaError> {
+        let rotors: Vec<&str> = self.rotors.iter().map(String::as_str).collect();
+        Enigma::from_preset(
+            model,
+            &self.reflector,
+            &rotors,
+            &self.rings,
+            &self.positions,
+            &self.plugboard,
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeySheet {
+    pub model: Model,
+    pub keys: Vec<DailyKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TextKeySheet {
+    model: String,
+    days: Vec<TextDailyKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TextDailyKey {
+    date: String,
+    reflector: String,
+    rotors: Vec<String>,
+    rings: String,
+    positions: String,
+    plugboard: String,
+}
+
+impl KeySheet {
+    // Одинаковые модель, даты и зерно дают одинаковую таблицу. ChaCha20, а не
+    // StdRng: алгоритм StdRng может смениться с версией rand, и зерно из
+    // распечатки перестало бы воспроизводить таблицу
+    pub fn generate(model: Model, start: Date, days: u32, seed: u64) -> Self {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+        KeySheet {
+            model,
+            keys: (0..days)
+                .map(|i| DailyKey::generate(model, start.add_days(i as i64), &mut rng))
+                .collect(),
+        }
+    }
+
+    pub fn key(&self, date: Date) -> Option<&DailyKey> {
+        self.keys.iter().find(|key| key.date == date)
+    }
+
+    pub fn enigma<T: Symbol + From<u8>>(&self, date: Date) -> Result<Enigma<T>, EnigmaError> {
+        self.key(date)
+            .ok_or(EnigmaError::InvalidKeySheet("no key for date"))?
+            .enigma(self.model)
+    }
+
+    // Таблица для печати. Дни идут в обратном порядке, как в настоящих
+    // таблицах: использованную строку отрезали снизу
+    pub fn to_printable(&self) -> String {
+        let mut text = format!("Enigma {} key sheet\n\n", self.model);
+        text.push_str(&format!(
+            "{:<10} | {:<9} | {:<16} | {:<5} | {:<9} | {}\n",
+            "Date", "Reflector", "Rotors", "Rings", "Positions", "Plugboard"
+        ));
+        text.push_str(&format!("{}\n", "-".repeat(90)));
+
+        for key in self.keys.iter().rev() {
+            text.push_str(&format!(
+                "{:<10} | {:<9} | {:<16} | {:<5} | {:<9} | {}\n",
+                key.date.to_string(),
+                key.reflector,
+                key.rotors.join(" "),
+                key.rings,
+                key.positions,
+                key.plugboard
+            ));
+        }
+
+        text
+    }
+
+    pub fn save(&self, file: &mut File) -> Result<(), Error> {
+        let text_sheet = TextKeySheet {
+            model: self.model.to_string(),
+            days: self
+                .keys
+                .iter()
+                .map(|key| TextDailyKey {
+                    date: key.date.to_string(),
+                    reflector: key.reflector.clone(),
+                    rotors: key.rotors.clone(),
+                    rings: key.rings.clone(),
+                    positions: key.positions.clone(),
+                    plugboard: key.plugboard.clone(),
+                })
+                .collect(),
+        };
+
+        let text =
+            toml::to_string(&text_sheet).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        file.write_all(text.as_bytes())
+    }
+
+    pub fn load(file: &mut File) -> Result<Self, Error> {
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+
+        let text_sheet: TextKeySheet =
+            toml::from_str(&text).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let invalid_data = |e: EnigmaError| Error::new(ErrorKind::InvalidData, e);
+
+        Ok(KeySheet {
+            model: text_sheet.model.parse().map_err(invalid_data)?,
+            keys: text_sheet
+                .days
+                .into_iter()
+                .map(|key| {
+                    Ok(DailyKey {
+                        date: key.date.parse().map_err(invalid_data)?,
+                        reflector: key.reflector,
+                        rotors: key.rotors,
+                        rings: key.rings,
+                        positions: key.positions,
+                        plugboard: key.plugboard,
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+}
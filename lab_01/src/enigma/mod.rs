@@ -1,144 +1,506 @@
+pub mod analysis;
 pub mod cfg;
+pub mod error;
+pub mod keysheet;
+pub mod preset;
+pub mod repl;
+pub mod stepping;
+pub mod stream;
+pub mod symbol;
+pub mod text;
+pub mod trace;
 
+mod entry;
+mod indicator;
+mod parallel;
+mod plugboard;
 mod reflector;
 mod rotor;
 
+pub use plugboard::Plugboard;
+
+pub use error::{Component, EnigmaError};
+pub use symbol::Symbol;
+
+use rand::{Rng, SeedableRng, rng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+use cfg::{EnigmaConfig, EnigmaState};
+use entry::EntryWheel;
 use reflector::Reflector;
 use rotor::Rotor;
+use stepping::{EnigmaStepping, LegacyStepping, Stepping, Wheels};
+use symbol::SymbolIndex;
+
+// Что делать с символами не из алфавита. Пропущенные и выброшенные
+// символы не вращают роторы
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownSymbols {
+    #[default]
+    Error,
+    PassThrough,
+    Drop,
+}
+
+// Механика машины. Версия 0.1 поворачивала роторы после шифрования символа,
+// не сдвигала выход ротора обратно на его позицию и поворачивала следующий
+// ротор, пока предыдущий стоит в начальной позиции. С появлением исторических
+// моделей все машины, в том числе случайные, работают как настоящая Энигма,
+// поэтому шифротексты версии 0.1 расшифровывает только машина с механикой
+// `Legacy`. Механика сохраняется в конфигурации
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mechanics {
+    #[default]
+    Historical,
+    Legacy,
+}
 
-pub struct Enigma<T> {
-    commutator: Option<Reflector<T>>,
+impl Mechanics {
+    fn stepping(self) -> Box<dyn Stepping> {
+        match self {
+            Mechanics::Historical => Box::new(EnigmaStepping),
+            Mechanics::Legacy => Box::new(LegacyStepping),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Enigma<T: Symbol> {
+    alphabet: Vec<T>,
+    index: T::Index,
+    commutator: Option<Plugboard<T>>,
+    plugs: Vec<usize>,
+    entry: Option<EntryWheel<T>>,
     reflector: Reflector<T>,
     rotors: Vec<Rotor<T>>,
+    // Номера вращающихся роторов, неподвижные механизм поворота не видит
+    moving: Vec<usize>,
+    mechanics: Mechanics,
+    stepping: Box<dyn Stepping>,
+    unknown: UnknownSymbols,
 }
 
-impl<T: Clone + Eq + Ord> Enigma<T> {
+impl<T: Symbol> Enigma<T> {
     pub fn from_alphabet(
         alphabet: &[T],
         rotors_cnt: u8,
         with_commutator: bool,
-    ) -> Result<Self, &str> {
+    ) -> Result<Self, EnigmaError> {
+        Enigma::from_alphabet_with_rng(alphabet, rotors_cnt, with_commutator, &mut rng())
+    }
+
+    // Машина зависит только от набора символов алфавита, но не от их порядка
+    pub fn from_alphabet_with_rng<R: Rng + ?Sized>(
+        alphabet: &[T],
+        rotors_cnt: u8,
+        with_commutator: bool,
+        rng: &mut R,
+    ) -> Result<Self, EnigmaError> {
+        let sorted_alphabet = cfg::sorted_alphabet(alphabet)?;
+
         let commutator = if with_commutator {
-            Some(Reflector::from_alphabet(alphabet))
+            Some(Plugboard::from_alphabet_with_rng(&sorted_alphabet, rng))
         } else {
             None
         };
 
-        let reflector = Reflector::from_alphabet(alphabet);
+        let reflector = Reflector::from_alphabet(&sorted_alphabet, rng);
         let rotors = (0..rotors_cnt)
-            .map(|_| Rotor::from_alphabet(alphabet))
+            .map(|_| Rotor::from_alphabet(&sorted_alphabet, rng))
             .collect();
 
-        Ok(Enigma {
+        Ok(Enigma::assemble(
+            sorted_alphabet,
             commutator,
+            None,
             reflector,
             rotors,
-        })
+            Mechanics::Historical,
+        ))
     }
 
-    pub fn from_config<'a>(
-        commutator_config: Option<&'a [T]>,
-        reflector_config: &'a [T],
-        rotors_configs: &'a [Vec<T>],
-    ) -> Result<Self, &'a str> {
-        let commutator = if let Some(cfg) = commutator_config {
-            Some(Reflector::from_config(cfg))
+    // Генератор ChaCha20 выбран потому, что его последовательность
+    // не зависит от платформы и версии rand: одинаковое зерно дает одинаковую
+    // машину у обеих сторон
+    pub fn from_seed(
+        alphabet: &[T],
+        rotors_cnt: u8,
+        with_commutator: bool,
+        seed: u64,
+    ) -> Result<Self, EnigmaError> {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        Enigma::from_alphabet_with_rng(alphabet, rotors_cnt, with_commutator, &mut rng)
+    }
+
+    // Зерно генератора - SHA-256 от парольной фразы
+    pub fn from_passphrase(
+        alphabet: &[T],
+        rotors_cnt: u8,
+        with_commutator: bool,
+        passphrase: &str,
+    ) -> Result<Self, EnigmaError> {
+        let mut rng = ChaCha20Rng::from_seed(Sha256::digest(passphrase.as_bytes()).into());
+        Enigma::from_alphabet_with_rng(alphabet, rotors_cnt, with_commutator, &mut rng)
+    }
+
+    pub fn from_config(config: &EnigmaConfig<T>) -> Result<Self, EnigmaError> {
+        config.validate()?;
+
+        let commutator = if let Some(cfg) = &config.commutator {
+            Some(Plugboard::from_config(cfg)?)
         } else {
             None
         };
 
-        let reflector = Reflector::from_config(reflector_config);
-        let rotors = (0..rotors_configs.len())
-            .map(|i| {
-                if i == 0 {
-                    Rotor::from_config(&rotors_configs[i])
-                } else if rotors_configs[i - 1].len() == rotors_configs[i].len() {
-                    Rotor::from_config(&rotors_configs[i])
-                } else {
-                    panic!("Different sizes of rotor configs")
-                }
-            })
+        let entry = config.entry.as_deref().map(EntryWheel::from_config);
+        let reflector = Reflector::from_config(&config.reflector);
+        let rotors = config
+            .rotors
+            .iter()
+            .map(|rotor| Rotor::from_config(rotor, config.mechanics))
             .collect();
 
-        Ok(Enigma {
+        Ok(Enigma::assemble(
+            cfg::sorted_alphabet(&config.alphabet)?,
             commutator,
+            entry,
             reflector,
             rotors,
+            config.mechanics,
+        ))
+    }
+
+    fn assemble(
+        alphabet: Vec<T>,
+        commutator: Option<Plugboard<T>>,
+        entry: Option<EntryWheel<T>>,
+        reflector: Reflector<T>,
+        rotors: Vec<Rotor<T>>,
+        mechanics: Mechanics,
+    ) -> Self {
+        let plugs = match &commutator {
+            Some(commutator) => commutator.table(&alphabet),
+            None => (0..alphabet.len()).collect(),
+        };
+        let moving = (0..rotors.len())
+            .filter(|&i| !rotors[i].is_stationary())
+            .collect();
+
+        Enigma {
+            index: T::Index::new(&alphabet),
+            alphabet,
+            commutator,
+            plugs,
+            entry,
+            reflector,
+            rotors,
+            moving,
+            mechanics,
+            stepping: mechanics.stepping(),
+            unknown: UnknownSymbols::default(),
+        }
+    }
+
+    pub fn get_config(&self) -> EnigmaConfig<T> {
+        EnigmaConfig {
+            alphabet: self.alphabet.clone(),
+            commutator: self.commutator.as_ref().map(|c| c.get_config()),
+            entry: self.entry.as_ref().map(|e| e.get_config()),
+            reflector: self.reflector.get_config(),
+            rotors: self.rotors.iter().map(|rotor| rotor.get_config()).collect(),
+            mechanics: self.mechanics,
+        }
+    }
+
+    // Машина в состоянии снимка: `reset` вернет ее к начальным позициям
+    // из конфигурации
+    pub fn from_state(state: &EnigmaState<T>) -> Result<Self, EnigmaError> {
+        let mut enigma = Enigma::from_config(&state.config)?;
+        enigma.set_positions(&state.positions)?;
+
+        Ok(enigma)
+    }
+
+    // Снимок машины с другим механизмом поворота продолжил бы шифрование
+    // не с того места, поэтому он не создается
+    pub fn get_state(&self) -> Result<EnigmaState<T>, EnigmaError> {
+        if self.stepping.mechanics() != Some(self.mechanics) {
+            return Err(EnigmaError::UnsavedStepping);
+        }
+
+        Ok(EnigmaState {
+            config: self.get_config(),
+            positions: self.positions(),
         })
     }
 
-    pub fn get_config(&self) -> (Option<Vec<T>>, Vec<T>, Vec<Vec<T>>) {
-        (
-            self.commutator.as_ref().map(|c| c.get_config()),
-            self.reflector.get_config(),
-            self.rotors.iter().map(|rotor| rotor.get_config()).collect(),
-        )
+    pub fn set_plugboard(&mut self, plugboard: Option<Plugboard<T>>) -> Result<(), EnigmaError> {
+        if let Some(plugboard) = &plugboard
+            && plugboard
+                .get_config()
+                .iter()
+                .any(|symb| self.alphabet.binary_search(symb).is_err())
+        {
+            return Err(EnigmaError::InvalidPlugboard("symbol not in alphabet"));
+        }
+
+        self.plugs = match &plugboard {
+            Some(plugboard) => plugboard.table(&self.alphabet),
+            None => (0..self.alphabet.len()).collect(),
+        };
+        self.commutator = plugboard;
+        Ok(())
+    }
+
+    // Соединяет кабелем символы `a` и `b`, прежние кабели этих символов
+    // вынимаются
+    pub fn plug(&mut self, a: &T, b: &T) -> Result<(), EnigmaError> {
+        let mut pairs = self.unplugged(&[a, b]);
+        pairs.extend([a.clone(), b.clone()]);
+
+        self.set_plugboard(Some(Plugboard::from_config(&pairs)?))
     }
 
-    fn encrypt_symbol(&mut self, symbol: &T) -> Result<T, &'static str> {
-        let mut encrypt_symb = symbol.clone();
+    pub fn unplug(&mut self, symbol: &T) -> Result<(), EnigmaError> {
+        let pairs = self.unplugged(&[symbol]);
 
-        if let Some(commutator) = &self.commutator {
-            encrypt_symb = commutator
-                .reflect(&encrypt_symb)
-                .ok_or("Symbol not in alphabet")?;
+        if pairs.is_empty() {
+            self.set_plugboard(None)
+        } else {
+            self.set_plugboard(Some(Plugboard::from_config(&pairs)?))
+        }
+    }
+
+    // Пары коммутатора без кабелей, подключенных к `symbols`
+    fn unplugged(&self, symbols: &[&T]) -> Vec<T> {
+        let pairs = self
+            .commutator
+            .as_ref()
+            .map(|c| c.get_config())
+            .unwrap_or_default();
+
+        pairs
+            .chunks(2)
+            .filter(|pair| !pair.iter().any(|symb| symbols.contains(&symb)))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    // Механизм поворота не входит в конфигурацию, по умолчанию он задается
+    // механикой машины. С другим механизмом `get_state` не создает снимок
+    pub fn set_stepping(&mut self, stepping: Box<dyn Stepping>) {
+        self.stepping = stepping;
+        self.stepping.reset();
+    }
+
+    pub fn set_unknown_symbols(&mut self, unknown: UnknownSymbols) {
+        self.unknown = unknown;
+    }
+
+    pub fn positions(&self) -> Vec<T> {
+        self.rotors.iter().map(|rotor| rotor.get_position()).collect()
+    }
+
+    // Позиции задаются в том же порядке, что возвращает `positions`,
+    // начальные позиции для `reset` не меняются
+    pub fn set_positions(&mut self, positions: &[T]) -> Result<(), EnigmaError> {
+        if positions.len() != self.rotors.len() {
+            return Err(EnigmaError::PositionsCount {
+                expected: self.rotors.len(),
+                actual: positions.len(),
+            });
+        }
+
+        let indices = positions
+            .iter()
+            .enumerate()
+            .map(|(i, symb)| {
+                self.index
+                    .get(symb)
+                    .ok_or(EnigmaError::InvalidRotorSetting(i))
+            })
+            .collect::<Result<Vec<usize>, EnigmaError>>()?;
+
+        for (rotor, index) in self.rotors.iter_mut().zip(indices) {
+            rotor.set_position(index);
+        }
+
+        Ok(())
+    }
+
+    // Путь сигнала через коммутатор, входное колесо, роторы и рефлектор без
+    // вращения роторов, вход и выход - номера символов в отсортированном алфавите
+    pub(crate) fn substitute(&self, index: usize) -> usize {
+        let mut index = self.plugs[index];
+        if let Some(entry) = &self.entry {
+            index = entry.forward(index);
         }
 
         for rotor in &self.rotors {
-            encrypt_symb = rotor
-                .forward(&encrypt_symb)
-                .ok_or("Symbol not in alphabet")?;
+            index = rotor.forward(index);
         }
 
-        encrypt_symb = self
-            .reflector
-            .reflect(&encrypt_symb)
-            .ok_or("Symbol not in alphabet")?;
+        index = self.reflector.reflect(index);
 
         for rotor in self.rotors.iter().rev() {
-            encrypt_symb = rotor
-                .backward(&encrypt_symb)
-                .ok_or("Symbol not in alphabet")?;
+            index = rotor.backward(index);
         }
 
-        if let Some(commutator) = &self.commutator {
-            encrypt_symb = commutator
-                .reflect(&encrypt_symb)
-                .ok_or("Symbol not in alphabet")?;
+        if let Some(entry) = &self.entry {
+            index = entry.backward(index);
         }
+        self.plugs[index]
+    }
 
-        self.rotate_rotors();
+    // Нажатие клавиши: поворот роторов и путь сигнала
+    pub(crate) fn press(&mut self, index: usize) -> usize {
+        match self.mechanics {
+            Mechanics::Historical => {
+                self.rotate_rotors();
+                self.substitute(index)
+            }
+            Mechanics::Legacy => {
+                let output = self.substitute(index);
+                self.rotate_rotors();
+                output
+            }
+        }
+    }
 
-        Ok(encrypt_symb.clone())
+    fn encrypt_symbol(&mut self, symbol: &T) -> Option<T> {
+        let index = self.index.get(symbol)?;
+        let output = self.press(index);
+
+        Some(self.alphabet[output].clone())
     }
 
-    pub fn encrypt(&mut self, buf: &[T]) -> Result<Vec<T>, (usize, &'static str)> {
+    pub fn encrypt(&mut self, buf: &[T]) -> Result<Vec<T>, EnigmaError> {
         let mut ebuf = Vec::with_capacity(buf.len());
 
         for (i, symb) in buf.iter().enumerate() {
-            ebuf.push(self.encrypt_symbol(symb).map_err(|err_str| (i, err_str))?);
+            match self.encrypt_symbol(symb) {
+                Some(esymb) => ebuf.push(esymb),
+                None => match self.unknown {
+                    UnknownSymbols::Error => return Err(EnigmaError::SymbolNotInAlphabet(i)),
+                    UnknownSymbols::PassThrough => ebuf.push(symb.clone()),
+                    UnknownSymbols::Drop => {}
+                },
+            }
         }
 
         Ok(ebuf)
     }
 
-    pub fn decrypt(&mut self, buf: &[T]) -> Result<Vec<T>, (usize, &'static str)> {
+    pub fn decrypt(&mut self, buf: &[T]) -> Result<Vec<T>, EnigmaError> {
         self.encrypt(buf)
     }
 
-    fn rotate_rotors(&mut self) {
-        for i in 0..self.rotors.len() {
-            if i == 0 {
-                self.rotors[i].rotate();
-            } else if self.rotors[i - 1].is_at_init_position() {
-                self.rotors[i].rotate();
-            }
+    // Возвращает длину результата: при `UnknownSymbols::Drop` оставшиеся
+    // символы сдвигаются к началу буфера
+    pub fn encrypt_in_place(&mut self, buf: &mut [T]) -> Result<usize, EnigmaError> {
+        let mut len = 0;
+
+        for i in 0..buf.len() {
+            let esymb = match self.encrypt_symbol(&buf[i]) {
+                Some(esymb) => esymb,
+                None => match self.unknown {
+                    UnknownSymbols::Error => return Err(EnigmaError::SymbolNotInAlphabet(i)),
+                    UnknownSymbols::PassThrough => buf[i].clone(),
+                    UnknownSymbols::Drop => continue,
+                },
+            };
+
+            buf[len] = esymb;
+            len += 1;
         }
+
+        Ok(len)
+    }
+
+    fn rotate_rotors(&mut self) {
+        self.stepping.step(&mut Wheels::new(&mut self.rotors, &self.moving));
     }
 
     pub fn reset(&mut self) {
         for rotor in &mut self.rotors {
             rotor.reset();
         }
+        self.stepping.reset();
+    }
+
+    // Устанавливает роторы в состояние после шифрования `offset` символов
+    // с начальных позиций
+    pub fn seek(&mut self, offset: usize) {
+        self.reset();
+        self.advance(offset);
+    }
+
+    // Поворачивает роторы так же, как `steps` нажатий клавиш.
+    // Пока ни один вращаемый механизмом ротор, кроме последнего, не стоит
+    // на вырезе, вращается только быстрый ротор, поэтому участки до его
+    // ближайшего выреза проходятся за один шаг, а поштучно моделируются
+    // только переносы.
+    // Регулярный механизм не имеет своего состояния, поэтому, как только
+    // позиции роторов после переноса повторились, остаток шагов сокращается
+    // по модулю периода. Цикл ищется алгоритмом Брента без запоминания
+    // всех позиций. Итого O(min(steps, период) / длина алфавита) переносов:
+    // для трех роторов Энигмы I период 16900, и перемотка на 10^9 символов
+    // мгновенна. Нерегулярные механизмы моделируются поштучно, за O(steps)
+    pub fn advance(&mut self, mut steps: usize) {
+        if !self.stepping.is_regular() {
+            for _ in 0..steps {
+                self.rotate_rotors();
+            }
+            return;
+        }
+        let driven = self.moving[self.stepping.driven(self.moving.len())].to_vec();
+        let Some(&fast) = driven.first() else {
+            return;
+        };
+        let carrying = driven.len() - 1;
+
+        // Контрольные позиции и оставшиеся в тот момент шаги
+        let mut checkpoint: (Vec<usize>, usize) = (Vec::new(), 0);
+        let mut since_checkpoint = 0;
+        let mut checkpoint_interval = 1;
+        let mut looped = false;
+
+        while steps > 0 {
+            if driven[..carrying]
+                .iter()
+                .any(|&i| self.rotors[i].is_at_notch())
+            {
+                self.rotate_rotors();
+                steps -= 1;
+
+                if !looped {
+                    let positions: Vec<usize> =
+                        self.moving.iter().map(|&i| self.rotors[i].index()).collect();
+                    if positions == checkpoint.0 {
+                        steps %= checkpoint.1 - steps;
+                        looped = true;
+                    } else {
+                        since_checkpoint += 1;
+                        if since_checkpoint == checkpoint_interval {
+                            checkpoint = (positions, steps);
+                            since_checkpoint = 0;
+                            checkpoint_interval *= 2;
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let jump = match carrying {
+                0 => steps,
+                _ => self.rotors[fast]
+                    .steps_to_notch()
+                    .map_or(steps, |k| k.min(steps)),
+            };
+            self.rotors[fast].rotate_by(jump);
+            steps -= jump;
+        }
     }
 }
@@ -0,0 +1,106 @@
+use std::thread;
+
+use super::{
+    Enigma, UnknownSymbols,
+    error::EnigmaError,
+    symbol::{Symbol, SymbolIndex},
+};
+
+// Меньшие части не окупают создание потока и копирование машины
+const MIN_CHUNK_SIZE: usize = 4096;
+
+impl<T: Symbol + Send + Sync> Enigma<T> {
+    // Результат совпадает с `encrypt`: буфер делится на части, и каждую
+    // часть шифрует своя копия машины, заранее переведенная в состояние
+    // на начало части
+    pub fn encrypt_parallel(&mut self, buf: &[T], threads: usize) -> Result<Vec<T>, EnigmaError> {
+        let mut ebuf = buf.to_vec();
+        let len = self.encrypt_in_place_parallel(&mut ebuf, threads)?;
+        ebuf.truncate(len);
+
+        Ok(ebuf)
+    }
+
+    pub fn decrypt_parallel(&mut self, buf: &[T], threads: usize) -> Result<Vec<T>, EnigmaError> {
+        self.encrypt_parallel(buf, threads)
+    }
+
+    // Возвращает длину результата, как `encrypt_in_place`
+    pub fn encrypt_in_place_parallel(
+        &mut self,
+        buf: &mut [T],
+        threads: usize,
+    ) -> Result<usize, EnigmaError> {
+        // Нерегулярный механизм перематывается поштучно, и каждая копия
+        // машины прошла бы весь путь до своей части - дольше, чем шифровать
+        // в одном потоке
+        if !self.stepping.is_regular() {
+            return self.encrypt_in_place(buf);
+        }
+
+        let chunk_size = buf.len().div_ceil(threads.max(1)).max(MIN_CHUNK_SIZE);
+
+        // Символы не из алфавита роторы не вращают, поэтому начальное
+        // состояние части зависит от числа символов алфавита перед ней
+        let steps = |enigma: &Self, symbols: &[T]| match enigma.unknown {
+            UnknownSymbols::Error => symbols.len(),
+            _ => symbols
+                .iter()
+                .filter(|symb| enigma.index.get(symb).is_some())
+                .count(),
+        };
+
+        let mut offset = 0;
+        let results: Vec<Result<usize, EnigmaError>> = thread::scope(|s| {
+            let workers: Vec<_> = buf
+                .chunks_mut(chunk_size)
+                .enumerate()
+                .map(|(i, chunk)| {
+                    let mut enigma = self.clone();
+                    let start = offset;
+                    offset += steps(self, chunk);
+
+                    s.spawn(move || {
+                        enigma.advance(start);
+                        enigma.encrypt_in_place(chunk).map_err(|e| match e {
+                            EnigmaError::SymbolNotInAlphabet(pos) => {
+                                EnigmaError::SymbolNotInAlphabet(i * chunk_size + pos)
+                            }
+                            e => e,
+                        })
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("Encryption thread panicked"))
+                .collect()
+        });
+
+        // Как и после `encrypt`, роторы сдвинуты на число обработанных символов.
+        // Ошибка возможна только при `UnknownSymbols::Error`, когда каждый
+        // символ до нее вращал роторы
+        let lens = match results.into_iter().collect::<Result<Vec<usize>, _>>() {
+            Ok(lens) => lens,
+            Err(EnigmaError::SymbolNotInAlphabet(pos)) => {
+                self.advance(pos);
+                return Err(EnigmaError::SymbolNotInAlphabet(pos));
+            }
+            Err(e) => return Err(e),
+        };
+        self.advance(offset);
+
+        // Выброшенные символы оставляют в частях пропуски, которые
+        // закрываются сдвигом частей к началу буфера
+        let mut len = 0;
+        for (i, chunk_len) in lens.into_iter().enumerate() {
+            for j in 0..chunk_len {
+                buf.swap(len + j, i * chunk_size + j);
+            }
+            len += chunk_len;
+        }
+
+        Ok(len)
+    }
+}
@@ -0,0 +1,106 @@
+use rand::{Rng, rng, seq::SliceRandom};
+
+use super::error::EnigmaError;
+
+#[derive(Clone)]
+pub struct Plugboard<T> {
+    pairs: Vec<T>,
+}
+
+impl<T: Clone + Eq> Plugboard<T> {
+    pub fn from_alphabet(alphabet: &[T]) -> Self {
+        Plugboard::from_alphabet_with_rng(alphabet, &mut rng())
+    }
+
+    pub fn from_alphabet_with_rng<R: Rng + ?Sized>(alphabet: &[T], rng: &mut R) -> Self {
+        let mut pairs = alphabet.to_vec();
+        pairs.shuffle(rng);
+        pairs.truncate(alphabet.len() - alphabet.len() % 2);
+
+        Plugboard { pairs }
+    }
+
+    // Конфигурация - плоский список пар: [A, B, C, D] соединяет A-B и C-D,
+    // не упомянутые символы проходят через коммутатор без изменений
+    pub fn from_config(config: &[T]) -> Result<Self, EnigmaError> {
+        if !config.len().is_multiple_of(2) {
+            return Err(EnigmaError::InvalidPlugboard(
+                "cable must connect two symbols",
+            ));
+        }
+
+        for (i, symb) in config.iter().enumerate() {
+            if i % 2 == 0 && config[i + 1] == *symb {
+                return Err(EnigmaError::InvalidPlugboard(
+                    "cable can't connect symbol with itself",
+                ));
+            }
+            if config[i + 1..].contains(symb) {
+                return Err(EnigmaError::InvalidPlugboard(
+                    "symbol is used by several cables",
+                ));
+            }
+        }
+
+        Ok(Plugboard {
+            pairs: config.to_vec(),
+        })
+    }
+
+    pub fn get_config(&self) -> Vec<T> {
+        self.pairs.clone()
+    }
+
+    pub fn swap(&self, input: &T) -> T {
+        match self.pairs.iter().position(|x| x == input) {
+            Some(i) => self.pairs[i ^ 1].clone(),
+            None => input.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Ord> Plugboard<T> {
+    // Номер символа в отсортированном алфавите -> номер символа на другом
+    // конце кабеля, символы должны быть проверены заранее
+    pub(crate) fn table(&self, alphabet: &[T]) -> Vec<usize> {
+        let mut table: Vec<usize> = (0..alphabet.len()).collect();
+        let index_of = |symb: &T| {
+            alphabet
+                .binary_search(symb)
+                .expect("Plugboard symbol not in alphabet")
+        };
+
+        for pair in self.pairs.chunks(2) {
+            let (a, b) = (index_of(&pair[0]), index_of(&pair[1]));
+            table[a] = b;
+            table[b] = a;
+        }
+
+        table
+    }
+}
+
+impl<T: Clone + Eq + TryFrom<char>> Plugboard<T> {
+    // Запись пар через пробел, как в ключевых таблицах: "AV BS CG"
+    pub fn from_notation(notation: &str) -> Result<Self, EnigmaError> {
+        let mut config = Vec::new();
+
+        for cable in notation.split_whitespace() {
+            let symbols: Vec<char> = cable.chars().collect();
+            if symbols.len() != 2 {
+                return Err(EnigmaError::InvalidPlugboard(
+                    "cable must connect two symbols",
+                ));
+            }
+
+            for symb in symbols {
+                config.push(
+                    T::try_from(symb)
+                        .map_err(|_| EnigmaError::InvalidPlugboard("symbol not in alphabet"))?,
+                );
+            }
+        }
+
+        Plugboard::from_config(&config)
+    }
+}
@@ -0,0 +1,318 @@
+use std::{fmt, str::FromStr};
+
+use super::{
+    Enigma, Mechanics,
+    cfg::{EnigmaConfig, RotorConfig},
+    error::EnigmaError,
+    symbol::Symbol,
+};
+
+pub const LATIN_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+pub struct RotorWiring {
+    pub name: &'static str,
+    pub wiring: &'static str,
+    pub notches: &'static str,
+}
+
+pub struct ReflectorWiring {
+    pub name: &'static str,
+    pub wiring: &'static str,
+}
+
+pub const ROTORS: [RotorWiring; 8] = [
+    RotorWiring {
+        name: "I",
+        wiring: "EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+        notches: "Q",
+    },
+    RotorWiring {
+        name: "II",
+        wiring: "AJDKSIRUXBLHWTMCQGZNPYFVOE",
+        notches: "E",
+    },
+    RotorWiring {
+        name: "III",
+        wiring: "BDFHJLCPRTXVZNYEIWGAKMUSQO",
+        notches: "V",
+    },
+    RotorWiring {
+        name: "IV",
+        wiring: "ESOVPZJAYQUIRHXLNFTGKDCMWB",
+        notches: "J",
+    },
+    RotorWiring {
+        name: "V",
+        wiring: "VZBRGITYUPSDNHLXAWMJQOFECK",
+        notches: "Z",
+    },
+    RotorWiring {
+        name: "VI",
+        wiring: "JPGVOUMFYQBENHZRDKASXLICTW",
+        notches: "ZM",
+    },
+    RotorWiring {
+        name: "VII",
+        wiring: "NZJHGRCXMYSWBOUFAIVLPEKQDT",
+        notches: "ZM",
+    },
+    RotorWiring {
+        name: "VIII",
+        wiring: "FKQHTLXOCBJSPDZRAMEWNIUYGV",
+        notches: "ZM",
+    },
+];
+
+// Дополнительные ("греческие") роторы M4, устанавливаются только в левую позицию
+pub const GREEK_ROTORS: [RotorWiring; 2] = [
+    RotorWiring {
+        name: "Beta",
+        wiring: "LEYJVCNIXWPBQMDRTAKZGFUHOS",
+        notches: "",
+    },
+    RotorWiring {
+        name: "Gamma",
+        wiring: "FSOKANUERHMBTIYCWLQPZXVGJD",
+        notches: "",
+    },
+];
+
+pub const REFLECTORS: [ReflectorWiring; 5] = [
+    ReflectorWiring {
+        name: "A",
+        wiring: "EJMZALYXVBWFCRQUONTSPIKHGD",
+    },
+    ReflectorWiring {
+        name: "B",
+        wiring: "YRUHQSLDPXNGOKMIEBFZCWVJAT",
+    },
+    ReflectorWiring {
+        name: "C",
+        wiring: "FVPJIAOYEDRZXWGCTKUQSBNMHL",
+    },
+    ReflectorWiring {
+        name: "B-thin",
+        wiring: "ENKQAUYWJICOPBLMDXZVFTHRGS",
+    },
+    ReflectorWiring {
+        name: "C-thin",
+        wiring: "RDOBJNTKVEHMLFCWZAXGYIPSUQ",
+    },
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Model {
+    EnigmaI,
+    M3,
+    M4,
+}
+
+impl Model {
+    pub fn rotors(&self) -> &'static [&'static str] {
+        match self {
+            Model::EnigmaI => &["I", "II", "III", "IV", "V"],
+            Model::M3 | Model::M4 => &["I", "II", "III", "IV", "V", "VI", "VII", "VIII"],
+        }
+    }
+
+    pub fn reflectors(&self) -> &'static [&'static str] {
+        match self {
+            Model::EnigmaI => &["A", "B", "C"],
+            Model::M3 => &["B", "C"],
+            Model::M4 => &["B-thin", "C-thin"],
+        }
+    }
+
+    pub fn rotors_cnt(&self) -> usize {
+        match self {
+            Model::EnigmaI | Model::M3 => 3,
+            Model::M4 => 4,
+        }
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Model::EnigmaI => write!(f, "I"),
+            Model::M3 => write!(f, "M3"),
+            Model::M4 => write!(f, "M4"),
+        }
+    }
+}
+
+impl FromStr for Model {
+    type Err = EnigmaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "I" | "ENIGMA-I" => Ok(Model::EnigmaI),
+            "M3" => Ok(Model::M3),
+            "M4" => Ok(Model::M4),
+            _ => Err(EnigmaError::InvalidPreset("unknown Enigma model")),
+        }
+    }
+}
+
+pub fn rotor(name: &str) -> Option<&'static RotorWiring> {
+    ROTORS
+        .iter()
+        .chain(GREEK_ROTORS.iter())
+        .find(|r| r.name.eq_ignore_ascii_case(name))
+}
+
+pub fn reflector(name: &str) -> Option<&'static ReflectorWiring> {
+    REFLECTORS
+        .iter()
+        .find(|r| r.name.eq_ignore_ascii_case(name))
+}
+
+fn to_symbols<T: From<u8>>(s: &str) -> Vec<T> {
+    s.bytes().map(T::from).collect()
+}
+
+// Таблица подстановки рефлектора ("A->Y, B->R, ...") в пары, которые ожидает `Reflector`
+fn wiring_to_pairs<T: Clone + Eq>(alphabet: &[T], wiring: &[T]) -> Result<Vec<T>, EnigmaError> {
+    let mut pairs = Vec::with_capacity(alphabet.len());
+
+    for (i, symb) in alphabet.iter().enumerate() {
+        let j = alphabet
+            .iter()
+            .position(|x| x == &wiring[i])
+            .ok_or(EnigmaError::InvalidReflector)?;
+        if i == j || wiring[j] != *symb {
+            return Err(EnigmaError::InvalidReflector);
+        }
+        if i < j {
+            pairs.push(symb.clone());
+            pairs.push(wiring[i].clone());
+        }
+    }
+
+    Ok(pairs)
+}
+
+fn rotor_config<T: From<u8>>(wiring: &RotorWiring, ring: u8, position: u8) -> RotorConfig<T> {
+    RotorConfig {
+        wiring: to_symbols(wiring.wiring),
+        notches: to_symbols(wiring.notches),
+        ring: T::from(ring),
+        position: T::from(position),
+        stationary: false,
+    }
+}
+
+impl<T: Symbol + From<u8>> Enigma<T> {
+    // Роторы, кольцевые установки и начальные позиции перечисляются слева
+    // направо, как в исторических ключевых таблицах: ["I", "II", "III"]
+    // означает, что ротор III стоит справа (быстрый). Коммутатор задается
+    // парами через пробел ("AV BS CG"), пустая строка - без коммутатора
+    pub fn from_preset(
+        model: Model,
+        reflector_name: &str,
+        rotors_names: &[&str],
+        rings: &str,
+        positions: &str,
+        plugboard: &str,
+    ) -> Result<Self, EnigmaError> {
+        if rotors_names.len() != model.rotors_cnt() {
+            return Err(EnigmaError::InvalidPreset(
+                "wrong rotors count for Enigma model",
+            ));
+        }
+        if rings.len() != rotors_names.len() || positions.len() != rotors_names.len() {
+            return Err(EnigmaError::InvalidPreset(
+                "rings and positions must be set for every rotor",
+            ));
+        }
+        if !rings
+            .bytes()
+            .chain(positions.bytes())
+            .all(|b| LATIN_ALPHABET.as_bytes().contains(&b))
+        {
+            return Err(EnigmaError::InvalidPreset(
+                "rings and positions must be letters A-Z",
+            ));
+        }
+        if !model
+            .reflectors()
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(reflector_name))
+        {
+            return Err(EnigmaError::InvalidPreset(
+                "reflector is not available for Enigma model",
+            ));
+        }
+
+        let mut settings: Vec<(&str, u8, u8)> = rotors_names
+            .iter()
+            .zip(rings.bytes().zip(positions.bytes()))
+            .map(|(name, (ring, position))| (*name, ring, position))
+            .collect();
+
+        let greek_settings = if model == Model::M4 {
+            Some(settings.remove(0))
+        } else {
+            None
+        };
+
+        let alphabet: Vec<T> = to_symbols(LATIN_ALPHABET);
+
+        let mut rotors = Vec::with_capacity(settings.len());
+        for (name, ring, position) in settings.into_iter().rev() {
+            if !model.rotors().iter().any(|r| r.eq_ignore_ascii_case(name)) {
+                return Err(EnigmaError::InvalidPreset(
+                    "rotor is not available for Enigma model",
+                ));
+            }
+            let wiring = rotor(name).ok_or(EnigmaError::InvalidPreset("unknown rotor"))?;
+            rotors.push(rotor_config(wiring, ring, position));
+        }
+
+        let reflector_wiring: Vec<T> = to_symbols(
+            reflector(reflector_name)
+                .ok_or(EnigmaError::InvalidPreset("unknown reflector"))?
+                .wiring,
+        );
+
+        // Греческий ротор M4 стоит слева от медленного и никогда не вращается,
+        // но его кольцо и позиция входят в установку машины
+        if let Some((name, ring, position)) = greek_settings {
+            let greek = GREEK_ROTORS
+                .iter()
+                .find(|r| r.name.eq_ignore_ascii_case(name))
+                .ok_or(EnigmaError::InvalidPreset(
+                    "leftmost M4 rotor must be Beta or Gamma",
+                ))?;
+            rotors.push(RotorConfig {
+                stationary: true,
+                ..rotor_config(greek, ring, position)
+            });
+        }
+
+        let mut plugs = Vec::new();
+        for cable in plugboard.split_whitespace() {
+            if cable.len() != 2 {
+                return Err(EnigmaError::InvalidPlugboard(
+                    "cable must connect two symbols",
+                ));
+            }
+            if !cable
+                .bytes()
+                .all(|b| LATIN_ALPHABET.as_bytes().contains(&b))
+            {
+                return Err(EnigmaError::InvalidPlugboard("symbol not in alphabet"));
+            }
+            plugs.extend(cable.bytes().map(T::from));
+        }
+
+        Enigma::from_config(&EnigmaConfig {
+            reflector: wiring_to_pairs(&alphabet, &reflector_wiring)?,
+            alphabet,
+            commutator: (!plugs.is_empty()).then_some(plugs),
+            entry: None,
+            rotors,
+            mechanics: Mechanics::Historical,
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use rand::{rng, seq::SliceRandom};
+use rand::{Rng, rng, seq::SliceRandom};
 
 #[allow(dead_code)]
 pub struct StdReflector<T> {
@@ -39,22 +39,46 @@ impl<T: Clone + Eq> StdReflector<T> {
     }
 }
 
+#[derive(Clone)]
 pub struct Reflector<T> {
     alphabet: Vec<T>,
+    // Номер символа в отсортированном алфавите -> номер его пары
+    mapping: Vec<usize>,
 }
 
-impl<T: Clone + Eq> Reflector<T> {
-    pub fn from_alphabet(alphabet: &[T]) -> Self {
-        let mut rng = rng();
+impl<T: Clone + Ord> Reflector<T> {
+    pub fn from_alphabet<R: Rng + ?Sized>(alphabet: &[T], rng: &mut R) -> Self {
         let mut cipher = alphabet.to_vec();
-        cipher.shuffle(&mut rng);
+        cipher.shuffle(rng);
 
-        Reflector { alphabet: cipher }
+        Reflector::from_config(&cipher)
     }
 
+    // Пары идут подряд: [A, B, C, D] соединяет A-B и C-D, последний символ
+    // алфавита нечетной длины отражается сам в себя
     pub fn from_config(config: &[T]) -> Self {
+        let mut sorted_alphabet = config.to_vec();
+        sorted_alphabet.sort();
+
+        let index_of = |symb: &T| {
+            sorted_alphabet
+                .binary_search(symb)
+                .expect("Reflector symbol not in alphabet")
+        };
+
+        let mut mapping = vec![0; config.len()];
+        for (i, symb) in config.iter().enumerate() {
+            let pair = if i + 1 == config.len() && !config.len().is_multiple_of(2) {
+                i
+            } else {
+                i ^ 1
+            };
+            mapping[index_of(symb)] = index_of(&config[pair]);
+        }
+
         Reflector {
             alphabet: config.to_vec(),
+            mapping,
         }
     }
 
@@ -62,15 +86,7 @@ impl<T: Clone + Eq> Reflector<T> {
         self.alphabet.clone()
     }
 
-    pub fn reflect(&self, input: &T) -> Option<T> {
-        let index = self.alphabet.iter().position(|x| x == input);
-
-        index.map(|i|
-            if i == self.alphabet.len() - 1 && self.alphabet.len() % 2 != 0 {
-                self.alphabet[i].clone()
-            } else {
-                self.alphabet[if i % 2 == 0 { i + 1 } else { i - 1 }].clone()
-            }
-        )
+    pub fn reflect(&self, index: usize) -> usize {
+        self.mapping[index]
     }
 }
@@ -0,0 +1,167 @@
+use std::io::{self, BufRead, Write};
+
+use super::Enigma;
+
+// Расположение ламп латинской Энигмы
+const LAMPBOARD_ROWS: [&str; 3] = ["QWERTZUIO", "ASDFGHJK", "PYXCVBNML"];
+// Для других алфавитов лампы идут по порядку рядами такой длины
+const LAMPS_PER_ROW: usize = 11;
+
+const HELP: &str = "\
+Буквы - нажатие клавиш, каждая нажатая клавиша зажигает лампу.
+Команды:
+  :set ПОЗИЦИИ   установить роторы, позиции слева направо
+  :step [N]      повернуть роторы, как при N нажатиях (по умолчанию 1)
+  :plug AB ...   соединить пары символов кабелями коммутатора
+  :unplug A ...  вынуть кабели символов
+  :reset         вернуть роторы в начальные позиции
+  :help          эта справка
+  :quit          выход";
+
+pub fn run(enigma: &mut Enigma<char>, input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}\n", HELP)?;
+    show_state(enigma, out)?;
+
+    let mut lines = input.lines();
+    loop {
+        write!(out, "> ")?;
+        out.flush()?;
+
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line?;
+        let mut words = line.split_whitespace();
+
+        let Some(command) = words.next().and_then(|w| w.strip_prefix(':')) else {
+            press_keys(enigma, &line, out)?;
+            continue;
+        };
+        let args: Vec<&str> = words.collect();
+
+        let result = match command {
+            "set" => {
+                let positions: Vec<char> = args.concat().chars().rev().collect();
+                enigma
+                    .set_positions(&enigma.normalize_text(&positions.iter().collect::<String>()))
+                    .map_err(|e| e.to_string())
+            }
+            "step" => match args.first().map_or(Ok(1), |n| n.parse()) {
+                Ok(steps) => {
+                    enigma.advance(steps);
+                    Ok(())
+                }
+                Err(_) => Err(format!("Неверное число шагов '{}'", args[0])),
+            },
+            "plug" => args
+                .iter()
+                .try_for_each(|pair| match enigma.normalize_text(pair)[..] {
+                    [a, b] => enigma.plug(&a, &b).map_err(|e| e.to_string()),
+                    _ => Err(format!("Кабель соединяет два символа, а не '{}'", pair)),
+                }),
+            "unplug" => args.iter().try_for_each(|symbols| {
+                enigma
+                    .normalize_text(symbols)
+                    .iter()
+                    .try_for_each(|symb| enigma.unplug(symb))
+                    .map_err(|e| e.to_string())
+            }),
+            "reset" => {
+                enigma.reset();
+                Ok(())
+            }
+            "help" => {
+                writeln!(out, "{}", HELP)?;
+                continue;
+            }
+            "quit" | "q" => return Ok(()),
+            _ => Err(format!(
+                "Неизвестная команда ':{}', справка - :help",
+                command
+            )),
+        };
+
+        match result {
+            Ok(()) => show_state(enigma, out)?,
+            Err(e) => writeln!(out, "{}", e)?,
+        }
+    }
+}
+
+fn press_keys(enigma: &mut Enigma<char>, line: &str, out: &mut impl Write) -> io::Result<()> {
+    let mut lamps = String::new();
+
+    for key in enigma.normalize_text(line) {
+        match enigma.encrypt(&[key]) {
+            Ok(lamp) => lamps.extend(lamp),
+            Err(_) => {
+                writeln!(out, "Клавиши '{}' нет", key)?;
+                break;
+            }
+        }
+    }
+
+    if let Some(last) = lamps.chars().last() {
+        show_lampboard(enigma, last, out)?;
+        writeln!(out, "Лампы: {}", lamps)?;
+    }
+    show_state(enigma, out)
+}
+
+fn show_lampboard(enigma: &Enigma<char>, lit: char, out: &mut impl Write) -> io::Result<()> {
+    let alphabet = enigma.get_config().alphabet;
+    let latin: Vec<char> = ('A'..='Z').collect();
+
+    let rows: Vec<Vec<char>> = if alphabet == latin {
+        LAMPBOARD_ROWS
+            .iter()
+            .map(|row| row.chars().collect())
+            .collect()
+    } else {
+        alphabet
+            .chunks(LAMPS_PER_ROW)
+            .map(|row| row.to_vec())
+            .collect()
+    };
+
+    for (i, row) in rows.iter().enumerate() {
+        // Ряды сдвинуты друг относительно друга, как на панели машины
+        let lamps: String = row
+            .iter()
+            .map(|&lamp| {
+                if lamp == lit {
+                    format!("[{}]", lamp)
+                } else {
+                    format!(" {} ", lamp)
+                }
+            })
+            .collect();
+        writeln!(out, "{}{}", " ".repeat(i % 2 * 2), lamps)?;
+    }
+
+    Ok(())
+}
+
+fn show_state(enigma: &Enigma<char>, out: &mut impl Write) -> io::Result<()> {
+    let windows: Vec<String> = enigma
+        .positions()
+        .iter()
+        .rev()
+        .map(|position| format!("[{}]", position))
+        .collect();
+
+    let plugs: Vec<String> = enigma
+        .get_config()
+        .commutator
+        .unwrap_or_default()
+        .chunks(2)
+        .map(|pair| pair.iter().collect())
+        .collect();
+
+    writeln!(
+        out,
+        "Роторы: {}  Коммутатор: {}",
+        windows.join(" "),
+        plugs.join(" ")
+    )
+}
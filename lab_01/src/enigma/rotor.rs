@@ -1,71 +1,183 @@
-use rand::{rng, seq::SliceRandom};
+use rand::{Rng, seq::SliceRandom};
 
+use super::{Mechanics, cfg::RotorConfig};
+
+#[derive(Clone)]
 pub struct Rotor<T> {
     position: usize,
+    start_position: usize,
+    ring: usize,
     alphabet_len: usize,
-
-    forward_alphabet: Vec<T>,
-    backward_alphabet: Vec<T>,
+    notches: Vec<usize>,
+    stationary: bool,
+    mechanics: Mechanics,
+
+    // Отсортированный алфавит нужен только для перевода номеров в символы
+    // при сохранении конфигурации, само шифрование идет по номерам
+    alphabet: Vec<T>,
+    wiring: Vec<usize>,
+    inverse: Vec<usize>,
 }
 
 impl<T: Clone + Ord> Rotor<T> {
-    pub fn from_alphabet(alphabet: &[T]) -> Self {
-        let mut rng = rng();
-
+    pub fn from_alphabet<R: Rng + ?Sized>(alphabet: &[T], rng: &mut R) -> Self {
         let mut sorted_alphabet = alphabet.to_vec();
-        let mut shuffeled_alphabet = alphabet.to_vec();
+        sorted_alphabet.sort();
 
+        let mut wiring: Vec<usize> = (0..alphabet.len()).collect();
+        wiring.shuffle(rng);
+
+        Rotor::from_wiring(
+            sorted_alphabet,
+            wiring,
+            vec![alphabet.len().saturating_sub(1)],
+            0,
+            0,
+            false,
+            Mechanics::Historical,
+        )
+    }
+
+    // Конфигурация должна быть проверена `EnigmaConfig::validate`
+    pub fn from_config(config: &RotorConfig<T>, mechanics: Mechanics) -> Self {
+        let mut sorted_alphabet = config.wiring.to_vec();
         sorted_alphabet.sort();
-        shuffeled_alphabet.shuffle(&mut rng);
+
+        let index_of = |symb: &T| {
+            sorted_alphabet
+                .binary_search(symb)
+                .expect("Rotor setting not in alphabet")
+        };
+
+        let wiring = config.wiring.iter().map(index_of).collect();
+        let notches = config.notches.iter().map(index_of).collect();
+        let ring = index_of(&config.ring);
+        let position = index_of(&config.position);
+
+        Rotor::from_wiring(
+            sorted_alphabet,
+            wiring,
+            notches,
+            ring,
+            position,
+            config.stationary,
+            mechanics,
+        )
+    }
+
+    fn from_wiring(
+        alphabet: Vec<T>,
+        wiring: Vec<usize>,
+        notches: Vec<usize>,
+        ring: usize,
+        position: usize,
+        stationary: bool,
+        mechanics: Mechanics,
+    ) -> Self {
+        let mut inverse = vec![0; wiring.len()];
+        for (i, &j) in wiring.iter().enumerate() {
+            inverse[j] = i;
+        }
 
         Rotor {
             alphabet_len: alphabet.len(),
-            forward_alphabet: sorted_alphabet,
-            backward_alphabet: shuffeled_alphabet,
-            position: 0,
+            notches,
+            stationary,
+            mechanics,
+            alphabet,
+            wiring,
+            inverse,
+            position,
+            start_position: position,
+            ring,
         }
     }
 
-    pub fn from_config(config: &[T]) -> Self {
-        let mut sorted_alphabet = config.to_vec();
-        sorted_alphabet.sort();
+    pub fn get_config(&self) -> RotorConfig<T> {
+        let symbol = |&i: &usize| self.alphabet[i].clone();
 
-        Rotor {
-            alphabet_len: config.len(),
-            forward_alphabet: sorted_alphabet,
-            backward_alphabet: config.to_vec(),
-            position: 0,
+        RotorConfig {
+            wiring: self.wiring.iter().map(symbol).collect(),
+            notches: self.notches.iter().map(symbol).collect(),
+            ring: symbol(&self.ring),
+            position: symbol(&self.start_position),
+            stationary: self.stationary,
         }
     }
 
-    pub fn get_config(&self) -> Vec<T> {
-        self.backward_alphabet.clone()
+    pub fn is_stationary(&self) -> bool {
+        self.stationary
     }
 
-    pub fn forward(&self, input: &T) -> Option<T> {
-        let index = self.forward_alphabet.iter().position(|x| x == input);
+    pub fn get_position(&self) -> T {
+        self.alphabet[self.position].clone()
+    }
 
-        index.map(|i| self.backward_alphabet[(i + self.position) % self.alphabet_len].clone())
+    pub fn index(&self) -> usize {
+        self.position
     }
 
-    pub fn backward(&self, input: &T) -> Option<T> {
-        let index = self.backward_alphabet.iter().position(|x| x == input);
+    // Контакты ротора смещены относительно корпуса машины на разность
+    // позиции в окне и кольцевой установки: сигнал входит со сдвигом
+    // и выходит с обратным сдвигом
+    fn offset(&self) -> usize {
+        (self.position + self.alphabet_len - self.ring) % self.alphabet_len
+    }
+
+    // Вход и выход - номера символов в отсортированном алфавите.
+    // В механике версии 0.1 сдвиг учитывается только на стороне входа
+    // в проводку, а выход проводки не сдвигается обратно
+    pub fn forward(&self, index: usize) -> usize {
+        let offset = self.offset();
+        let wired = self.wiring[(index + offset) % self.alphabet_len];
+
+        match self.mechanics {
+            Mechanics::Historical => (wired + self.alphabet_len - offset) % self.alphabet_len,
+            Mechanics::Legacy => wired,
+        }
+    }
+
+    pub fn backward(&self, index: usize) -> usize {
+        let offset = self.offset();
+
+        match self.mechanics {
+            Mechanics::Historical => {
+                let wired = self.inverse[(index + offset) % self.alphabet_len];
+                (wired + self.alphabet_len - offset) % self.alphabet_len
+            }
+            Mechanics::Legacy => {
+                (self.inverse[index] + self.alphabet_len - offset) % self.alphabet_len
+            }
+        }
+    }
 
-        index.map(|i| {
-            self.forward_alphabet[(i + self.alphabet_len - self.position) % self.alphabet_len]
-                .clone()
-        })
+    // Находится ли в окне символ выреза: при следующем нажатии
+    // ротор провернет соседний ротор
+    pub fn is_at_notch(&self) -> bool {
+        self.notches.contains(&self.position)
     }
 
-    pub fn is_at_init_position(&self) -> bool {
-        self.position == 0
+    // Через сколько шагов в окне окажется ближайший вырез
+    pub fn steps_to_notch(&self) -> Option<usize> {
+        self.notches
+            .iter()
+            .map(|&notch| (notch + self.alphabet_len - self.position) % self.alphabet_len)
+            .min()
     }
 
     pub fn rotate(&mut self) {
-        self.position = (self.position + 1) % self.alphabet_len
+        self.rotate_by(1)
+    }
+
+    pub fn rotate_by(&mut self, steps: usize) {
+        self.position = (self.position + steps % self.alphabet_len) % self.alphabet_len
+    }
+
+    pub fn set_position(&mut self, index: usize) {
+        self.position = index % self.alphabet_len
     }
 
     pub fn reset(&mut self) {
-        self.position = 0
+        self.position = self.start_position
     }
 }
@@ -0,0 +1,258 @@
+use std::ops::Range;
+
+use super::{Mechanics, rotor::Rotor};
+
+// Доступ механизма поворота к роторам без знания типа символов.
+// Вращающиеся роторы пронумерованы от быстрого к медленному, как в
+// `Enigma::positions`, неподвижные роторы пропускаются
+pub struct Wheels<'a> {
+    rotors: &'a mut dyn RotorBank,
+    moving: &'a [usize],
+}
+
+trait RotorBank {
+    fn is_at_notch(&self, i: usize) -> bool;
+    fn rotate(&mut self, i: usize);
+}
+
+impl<T: Clone + Ord> RotorBank for Vec<Rotor<T>> {
+    fn is_at_notch(&self, i: usize) -> bool {
+        self[i].is_at_notch()
+    }
+
+    fn rotate(&mut self, i: usize) {
+        self[i].rotate()
+    }
+}
+
+impl<'a> Wheels<'a> {
+    pub(crate) fn new<T: Clone + Ord>(rotors: &'a mut Vec<Rotor<T>>, moving: &'a [usize]) -> Self {
+        Wheels { rotors, moving }
+    }
+
+    pub fn len(&self) -> usize {
+        self.moving.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moving.is_empty()
+    }
+
+    pub fn is_at_notch(&self, i: usize) -> bool {
+        self.rotors.is_at_notch(self.moving[i])
+    }
+
+    pub fn rotate(&mut self, i: usize) {
+        self.rotors.rotate(self.moving[i])
+    }
+}
+
+// Механизм, который поворачивает роторы перед шифрованием каждого символа
+pub trait Stepping: Send + Sync {
+    fn step(&mut self, wheels: &mut Wheels);
+
+    // Возврат собственного состояния механизма вместе с роторами
+    fn reset(&mut self) {}
+
+    // Без собственного состояния, и пока ни один ротор из `driven`, кроме
+    // последнего, не стоит на вырезе, нажатие поворачивает только первый
+    // из них. Тогда `Enigma::advance` проходит участки до выреза за один шаг
+    fn is_regular(&self) -> bool {
+        false
+    }
+
+    // Роторы, которые вращает механизм, номерами в `Wheels`: первый - быстрый.
+    // Остальные роторы для механизма неподвижны
+    fn driven(&self, wheels: usize) -> Range<usize> {
+        0..wheels
+    }
+
+    // Конфигурация и снимок состояния хранят только механику машины,
+    // `Enigma::from_config` восстанавливает по ней один из двух механизмов.
+    // Машину с другим механизмом из них не восстановить
+    fn mechanics(&self) -> Option<Mechanics> {
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn Stepping>;
+}
+
+impl Clone for Box<dyn Stepping> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+// Переносы с вырезов от быстрого ротора, первого в `driven`
+fn carry(wheels: &mut Wheels, driven: Range<usize>) {
+    for i in driven {
+        let at_notch = wheels.is_at_notch(i);
+        wheels.rotate(i);
+        if !at_notch {
+            break;
+        }
+    }
+}
+
+// Ротор проворачивает следующий, только когда сам поворачивается с выреза,
+// как колеса счетчика
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Odometer;
+
+impl Stepping for Odometer {
+    fn step(&mut self, wheels: &mut Wheels) {
+        carry(wheels, 0..wheels.len());
+    }
+
+    fn is_regular(&self) -> bool {
+        true
+    }
+
+    fn clone_box(&self) -> Box<dyn Stepping> {
+        Box::new(*self)
+    }
+}
+
+// Собачка i-го ротора цепляется за вырез (i-1)-го ротора и проворачивает
+// оба ротора сразу, отсюда двойной шаг среднего ротора. У последнего
+// ротора нет соседа слева, поэтому его вырезы ни на что не влияют
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnigmaStepping;
+
+impl Stepping for EnigmaStepping {
+    fn mechanics(&self) -> Option<Mechanics> {
+        Some(Mechanics::Historical)
+    }
+
+    fn step(&mut self, wheels: &mut Wheels) {
+        let rotors_cnt = wheels.len();
+
+        // От медленного ротора к быстрому, чтобы вырез соседа справа
+        // проверялся до его поворота
+        for i in (0..rotors_cnt).rev() {
+            let pushed_by_prev = i == 0 || wheels.is_at_notch(i - 1);
+            let pushes_next = i + 1 < rotors_cnt && wheels.is_at_notch(i);
+
+            if pushed_by_prev || pushes_next {
+                wheels.rotate(i);
+            }
+        }
+    }
+
+    fn is_regular(&self) -> bool {
+        true
+    }
+
+    fn clone_box(&self) -> Box<dyn Stepping> {
+        Box::new(*self)
+    }
+}
+
+// Механизм версии 0.1: быстрый ротор поворачивается при каждом нажатии,
+// а каждый следующий - если предыдущий после своего поворота стоит на вырезе,
+// даже если предыдущий в этот раз не поворачивался. У роторов из файлов
+// версии 0.1 вырез в начальной позиции
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LegacyStepping;
+
+impl Stepping for LegacyStepping {
+    fn step(&mut self, wheels: &mut Wheels) {
+        for i in 0..wheels.len() {
+            if i == 0 || wheels.is_at_notch(i - 1) {
+                wheels.rotate(i);
+            }
+        }
+    }
+
+    fn mechanics(&self) -> Option<Mechanics> {
+        Some(Mechanics::Legacy)
+    }
+
+    fn clone_box(&self) -> Box<dyn Stepping> {
+        Box::new(*self)
+    }
+}
+
+// Typex: как в исторической машине, вращаются три ротора со стороны
+// рефлектора, а роторы со стороны входа - статоры. Быстрый ротор стоит
+// сразу за статорами, переносы идут к рефлектору без двойного шага.
+// У роторов Typex по нескольку вырезов, поэтому переносы случаются часто
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Typex;
+
+impl Typex {
+    pub const MOVING_ROTORS: usize = 3;
+}
+
+impl Stepping for Typex {
+    fn step(&mut self, wheels: &mut Wheels) {
+        carry(wheels, self.driven(wheels.len()));
+    }
+
+    fn is_regular(&self) -> bool {
+        true
+    }
+
+    fn driven(&self, wheels: usize) -> Range<usize> {
+        wheels.saturating_sub(Typex::MOVING_ROTORS)..wheels
+    }
+
+    fn clone_box(&self) -> Box<dyn Stepping> {
+        Box::new(*self)
+    }
+}
+
+// Зубчатое колесо поворачивается на зуб при каждом нажатии, а быстрый ротор
+// поворачивается, только если на месте зуба есть выступ. Дальше переносы
+// как у счетчика. Колесо без выступов роторы не вращает
+#[derive(Clone, Debug)]
+pub struct CogWheel {
+    teeth: Vec<bool>,
+    position: usize,
+}
+
+impl CogWheel {
+    pub fn new(teeth: &[bool]) -> Self {
+        CogWheel {
+            teeth: teeth.to_vec(),
+            position: 0,
+        }
+    }
+
+    // Выступы обозначаются '1', пропуски - '0', например "1101001"
+    pub fn from_pattern(pattern: &str) -> Option<Self> {
+        let teeth = pattern
+            .chars()
+            .map(|c| match c {
+                '1' => Some(true),
+                '0' => Some(false),
+                _ => None,
+            })
+            .collect::<Option<Vec<bool>>>()?;
+
+        Some(CogWheel::new(&teeth))
+    }
+}
+
+impl Stepping for CogWheel {
+    fn step(&mut self, wheels: &mut Wheels) {
+        if self.teeth.is_empty() {
+            return;
+        }
+
+        let engaged = self.teeth[self.position];
+        self.position = (self.position + 1) % self.teeth.len();
+
+        if engaged {
+            carry(wheels, 0..wheels.len());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.position = 0;
+    }
+
+    fn clone_box(&self) -> Box<dyn Stepping> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::{self, Read, Write};
+
+use super::{Enigma, error::EnigmaError};
+
+const BUF_SIZE: usize = 64 * 1024;
+
+// Позиция ошибки пересчитывается от начала потока, а не текущего блока
+fn to_io_error(err: EnigmaError, processed: usize) -> io::Error {
+    let err = match err {
+        EnigmaError::SymbolNotInAlphabet(i) => EnigmaError::SymbolNotInAlphabet(processed + i),
+        err => err,
+    };
+
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+// Шифрует данные по мере чтения из `inner`
+pub struct EnigmaReader<'a, R> {
+    enigma: &'a mut Enigma<u8>,
+    inner: R,
+    processed: usize,
+}
+
+impl<R: Read> Read for EnigmaReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Если все прочитанные символы выброшены, читается следующий блок,
+        // чтобы пустой результат означал только конец потока
+        loop {
+            let n = self.inner.read(buf)?;
+
+            let len = self
+                .enigma
+                .encrypt_in_place(&mut buf[..n])
+                .map_err(|e| to_io_error(e, self.processed))?;
+            self.processed += n;
+
+            if len > 0 || n == 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+// Шифрует данные перед записью в `inner` блоками не больше `BUF_SIZE`
+pub struct EnigmaWriter<'a, W> {
+    enigma: &'a mut Enigma<u8>,
+    inner: W,
+    buf: Vec<u8>,
+    processed: usize,
+}
+
+impl<W: Write> Write for EnigmaWriter<'_, W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let n = data.len().min(BUF_SIZE);
+
+        self.buf.clear();
+        self.buf.extend_from_slice(&data[..n]);
+        let len = self
+            .enigma
+            .encrypt_in_place(&mut self.buf)
+            .map_err(|e| to_io_error(e, self.processed))?;
+        self.processed += n;
+
+        self.inner.write_all(&self.buf[..len])?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Enigma<u8> {
+    pub fn reader<R: Read>(&mut self, inner: R) -> EnigmaReader<'_, R> {
+        EnigmaReader {
+            enigma: self,
+            inner,
+            processed: 0,
+        }
+    }
+
+    pub fn writer<W: Write>(&mut self, inner: W) -> EnigmaWriter<'_, W> {
+        EnigmaWriter {
+            enigma: self,
+            inner,
+            buf: Vec::with_capacity(BUF_SIZE),
+            processed: 0,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::{collections::HashMap, hash::Hash};
+
+// Таблица "символ -> номер в отсортированном алфавите", строится один раз
+// при создании машины, чтобы поиск не зависел от размера алфавита
+pub trait SymbolIndex<T> {
+    fn new(alphabet: &[T]) -> Self;
+    fn get(&self, symbol: &T) -> Option<usize>;
+}
+
+pub trait Symbol: Clone + Ord + Hash {
+    type Index: SymbolIndex<Self> + Clone + Send + Sync;
+}
+
+#[derive(Clone)]
+pub struct DenseIndex {
+    table: [u16; 256],
+}
+
+impl SymbolIndex<u8> for DenseIndex {
+    fn new(alphabet: &[u8]) -> Self {
+        let mut table = [u16::MAX; 256];
+        for (i, &symb) in alphabet.iter().enumerate() {
+            table[symb as usize] = i as u16;
+        }
+
+        DenseIndex { table }
+    }
+
+    fn get(&self, symbol: &u8) -> Option<usize> {
+        match self.table[*symbol as usize] {
+            u16::MAX => None,
+            i => Some(i as usize),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MapIndex<T> {
+    table: HashMap<T, usize>,
+}
+
+impl<T: Clone + Eq + Hash> SymbolIndex<T> for MapIndex<T> {
+    fn new(alphabet: &[T]) -> Self {
+        MapIndex {
+            table: alphabet
+                .iter()
+                .enumerate()
+                .map(|(i, symb)| (symb.clone(), i))
+                .collect(),
+        }
+    }
+
+    fn get(&self, symbol: &T) -> Option<usize> {
+        self.table.get(symbol).copied()
+    }
+}
+
+impl Symbol for u8 {
+    type Index = DenseIndex;
+}
+
+macro_rules! map_symbol {
+    ($($t:ty),*) => {
+        $(
+            impl Symbol for $t {
+                type Index = MapIndex<$t>;
+            }
+        )*
+    };
+}
+
+map_symbol!(char, u16, u32, u64, usize, i8, i16, i32, i64, String);
@@ -0,0 +1,81 @@
+use super::{Enigma, error::EnigmaError, symbol::SymbolIndex};
+
+// Шифртекст записывается группами по пять символов, по десять групп в строке
+const GROUP_SIZE: usize = 5;
+const GROUPS_PER_LINE: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextAlphabet {
+    Latin,
+    // Без Ё: она заменяется на Е, как принято в шифровках
+    Russian,
+    RussianYo,
+    Custom(String),
+}
+
+impl TextAlphabet {
+    pub fn symbols(&self) -> Vec<char> {
+        match self {
+            TextAlphabet::Latin => ('A'..='Z').collect(),
+            TextAlphabet::Russian => ('А'..='Я').collect(),
+            TextAlphabet::RussianYo => ('А'..='Я').chain(['Ё']).collect(),
+            TextAlphabet::Custom(symbols) => symbols.chars().collect(),
+        }
+    }
+}
+
+pub fn to_groups(symbols: &[char]) -> String {
+    let groups: Vec<String> = symbols
+        .chunks(GROUP_SIZE)
+        .map(|group| group.iter().collect())
+        .collect();
+
+    groups
+        .chunks(GROUPS_PER_LINE)
+        .map(|line| line.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Enigma<char> {
+    // Символ приводится к алфавиту машины: строчные буквы заменяются
+    // заглавными, Ё - на Е, если ее нет в алфавите
+    fn normalize(&self, symbol: char) -> char {
+        let known = |symb: char| self.index.get(&symb).is_some();
+        if known(symbol) {
+            return symbol;
+        }
+
+        let mut upper = symbol.to_uppercase();
+        let upper = match (upper.next(), upper.next()) {
+            (Some(upper), None) => upper,
+            _ => return symbol,
+        };
+
+        match upper {
+            'Ё' if !known('Ё') && known('Е') => 'Е',
+            _ if known(upper) => upper,
+            _ => symbol,
+        }
+    }
+
+    // Пробельные символы не из алфавита считаются разбивкой текста
+    // и выбрасываются
+    pub fn normalize_text(&self, text: &str) -> Vec<char> {
+        text.chars()
+            .filter(|symb| !symb.is_whitespace() || self.index.get(symb).is_some())
+            .map(|symb| self.normalize(symb))
+            .collect()
+    }
+
+    // Позиция ошибки считается в тексте без разбивки
+    pub fn encrypt_text(&mut self, text: &str) -> Result<String, EnigmaError> {
+        let symbols = self.normalize_text(text);
+        Ok(to_groups(&self.encrypt(&symbols)?))
+    }
+
+    pub fn decrypt_text(&mut self, text: &str) -> Result<String, EnigmaError> {
+        let symbols = self.normalize_text(text);
+        Ok(self.decrypt(&symbols)?.into_iter().collect())
+    }
+}
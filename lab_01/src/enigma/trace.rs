@@ -0,0 +1,112 @@
+use super::{
+    Enigma, Mechanics, UnknownSymbols,
+    error::EnigmaError,
+    symbol::{Symbol, SymbolIndex},
+};
+
+// Путь одного символа через машину. Позиции роторов и проходы через роторы
+// перечисляются в порядке `positions`: от быстрого ротора к медленному,
+// обратный проход - в порядке прохождения сигнала, от медленного к быстрому
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolTrace<T> {
+    // Номер символа во входном буфере
+    pub index: usize,
+    pub input: T,
+    pub positions_before: Vec<T>,
+    pub positions_after: Vec<T>,
+    pub plugboard_in: T,
+    // Значения после входного колеса, если оно есть
+    pub entry_in: Option<T>,
+    pub forward: Vec<T>,
+    pub reflector: T,
+    pub backward: Vec<T>,
+    pub entry_out: Option<T>,
+    pub output: T,
+}
+
+impl<T: Symbol> Enigma<T> {
+    // Шифрует как `encrypt` и дополнительно возвращает путь каждого символа
+    // алфавита. Символы не из алфавита через машину не проходят и
+    // в трассировку не попадают
+    pub fn encrypt_traced(
+        &mut self,
+        buf: &[T],
+    ) -> Result<(Vec<T>, Vec<SymbolTrace<T>>), EnigmaError> {
+        let mut ebuf = Vec::with_capacity(buf.len());
+        let mut traces = Vec::with_capacity(buf.len());
+
+        for (i, symb) in buf.iter().enumerate() {
+            match self.trace_symbol(i, symb) {
+                Some(trace) => {
+                    ebuf.push(trace.output.clone());
+                    traces.push(trace);
+                }
+                None => match self.unknown {
+                    UnknownSymbols::Error => return Err(EnigmaError::SymbolNotInAlphabet(i)),
+                    UnknownSymbols::PassThrough => ebuf.push(symb.clone()),
+                    UnknownSymbols::Drop => {}
+                },
+            }
+        }
+
+        Ok((ebuf, traces))
+    }
+
+    // Повторяет `press`, запоминая промежуточные значения
+    fn trace_symbol(&mut self, index: usize, symbol: &T) -> Option<SymbolTrace<T>> {
+        let mut signal = self.index.get(symbol)?;
+        let positions_before = self.positions();
+        if self.mechanics == Mechanics::Historical {
+            self.rotate_rotors();
+        }
+
+        let symb = |i: usize| self.alphabet[i].clone();
+
+        signal = self.plugs[signal];
+        let plugboard_in = symb(signal);
+
+        let entry_in = self.entry.as_ref().map(|entry| {
+            signal = entry.forward(signal);
+            symb(signal)
+        });
+
+        let mut forward = Vec::with_capacity(self.rotors.len());
+        for rotor in &self.rotors {
+            signal = rotor.forward(signal);
+            forward.push(symb(signal));
+        }
+
+        signal = self.reflector.reflect(signal);
+        let reflector = symb(signal);
+
+        let mut backward = Vec::with_capacity(self.rotors.len());
+        for rotor in self.rotors.iter().rev() {
+            signal = rotor.backward(signal);
+            backward.push(symb(signal));
+        }
+
+        let entry_out = self.entry.as_ref().map(|entry| {
+            signal = entry.backward(signal);
+            symb(signal)
+        });
+        let output = symb(self.plugs[signal]);
+
+        if self.mechanics == Mechanics::Legacy {
+            self.rotate_rotors();
+        }
+
+        Some(SymbolTrace {
+            index,
+            input: symbol.clone(),
+            positions_before,
+            positions_after: self.positions(),
+            plugboard_in,
+            entry_in,
+            forward,
+            reflector,
+            backward,
+            entry_out,
+            output,
+        })
+    }
+}
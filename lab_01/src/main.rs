@@ -1,26 +1,128 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::{self, Error, Read, Write},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Error, Read, Seek, SeekFrom, Write},
 };
 
-use enigma::{Enigma, cfg::{BinConfigSerializer, ConfigSerializer}};
+use enigma::{
+    Enigma, EnigmaError, Plugboard, Symbol, UnknownSymbols,
+    analysis::{crack::Cracker, language::Language},
+    cfg::{BinConfigSerializer, ConfigSerializer, ConfigSymbol, EnigmaConfig, TomlConfigSerializer},
+    keysheet::{Date, KeySheet},
+    preset::Model,
+    repl,
+    text::{self, TextAlphabet},
+    trace::SymbolTrace,
+};
+
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+
+const STDIO_NAME: &str = "-";
+// Размер блока, который делится между потоками при многопоточном шифровании
+const PARALLEL_BLOCK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Формат конфигурационного файла
+#[derive(Clone, Copy, ValueEnum)]
+enum ConfigFormat {
+    /// Двоичный формат
+    Bin,
+    /// Текстовый формат TOML
+    Toml,
+}
+
+/// Язык открытого текста
+#[derive(Clone, Copy, ValueEnum)]
+enum TextLanguage {
+    /// Английский, буквы A-Z
+    English,
+    /// Русский, буквы А-Я (Ё заменяется на Е)
+    Russian,
+}
+
+impl From<TextLanguage> for Language {
+    fn from(language: TextLanguage) -> Self {
+        match language {
+            TextLanguage::English => Language::English,
+            TextLanguage::Russian => Language::Russian,
+        }
+    }
+}
+
+/// Алфавит текстового режима
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AlphabetName {
+    /// Латинские буквы A-Z
+    Latin,
+    /// Русские буквы А-Я (Ё заменяется на Е)
+    Russian,
+    /// Русские буквы А-Я и Ё
+    RussianYo,
+    /// Символы из --symbols
+    Custom,
+}
 
-use clap::{ArgAction, Parser};
+/// Обработка символов, которых нет в алфавите машины
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum UnknownPolicy {
+    /// Завершить шифрование с ошибкой
+    Error,
+    /// Оставить символ без изменений, роторы не вращаются
+    Pass,
+    /// Выбросить символ, роторы не вращаются
+    Drop,
+}
+
+impl From<UnknownPolicy> for UnknownSymbols {
+    fn from(policy: UnknownPolicy) -> Self {
+        match policy {
+            UnknownPolicy::Error => UnknownSymbols::Error,
+            UnknownPolicy::Pass => UnknownSymbols::PassThrough,
+            UnknownPolicy::Drop => UnknownSymbols::Drop,
+        }
+    }
+}
 
 /// Электронный аналог шифровальной машины "Энигма"
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
-    /// Имя шифруемого файла (обязательный)
-    filename: String,
+    #[command(subcommand)]
+    command: Command,
+}
 
+/// Установка машины: историческая модель, ключевая таблица, конфигурация,
+/// сохраненное состояние или случайная машина
+#[derive(Args)]
+struct MachineArgs {
     /// Имя конфигурационного файла рефлектора и роторов
     #[arg(short, long, value_name = "FILE")]
     config: Option<String>,
 
-    /// Имя зашифрованного выходного файла [default: e<FILENAME>]
-    #[arg(short, long, value_name = "FILE")]
-    out: Option<String>,
+    /// Формат конфигурационного файла (при чтении и сохранении)
+    #[arg(long, value_name = "FORMAT", value_enum, default_value_t = ConfigFormat::Bin)]
+    config_format: ConfigFormat,
+
+    /// Текстовый режим: шифруются буквы алфавита, а не байты
+    ///
+    /// Строчные буквы приводятся к заглавным, пробелы и переводы строк выбрасываются,
+    /// шифртекст записывается группами по пять букв.
+    #[arg(
+        long,
+        default_value_t = false,
+        action = ArgAction::SetTrue
+    )]
+    text: bool,
+
+    /// Алфавит случайной машины в текстовом режиме
+    #[arg(long, value_name = "NAME", value_enum, default_value_t = AlphabetName::Latin, requires = "text")]
+    alphabet: AlphabetName,
+
+    /// Символы собственного алфавита (для --alphabet custom)
+    #[arg(long, value_name = "SYMBOLS", required_if_eq("alphabet", "custom"))]
+    symbols: Option<String>,
+
+    /// Что делать с символами не из алфавита (пробелы, цифры, знаки препинания)
+    #[arg(long, value_name = "POLICY", value_enum, default_value_t = UnknownPolicy::Error)]
+    unknown: UnknownPolicy,
 
     /// Количество роторов (0-255)
     #[arg(
@@ -42,139 +144,889 @@ struct Cli {
         action = ArgAction::SetTrue
     )]
     with_commutator: bool,
+
+    /// Коммутатор из заданных пар символов, например "AV BS CG"
+    ///
+    /// Символы, не вошедшие ни в одну пару, проходят через коммутатор без изменений.
+    #[arg(
+        long,
+        value_name = "PAIRS",
+        conflicts_with_all = ["config", "with_commutator", "keysheet"]
+    )]
+    plugboard: Option<String>,
+
+    /// Зерно, из которого выводится случайная машина
+    ///
+    /// Одинаковые зерно, алфавит, количество роторов и --with-commutator дают
+    /// одинаковую машину, поэтому конфигурация сохраняется только по --save-config.
+    #[arg(long, value_name = "NUM", conflicts_with_all = ["config", "preset", "keysheet"])]
+    seed: Option<u64>,
+
+    /// Парольная фраза, из которой выводится случайная машина (как --seed)
+    #[arg(long, value_name = "TEXT", conflicts_with_all = ["config", "preset", "keysheet", "seed"])]
+    passphrase: Option<String>,
+
+    /// Продолжить шифрование с состояния, сохраненного --save-state
+    ///
+    /// Машина и положение роторов берутся из файла состояния, поэтому
+    /// цепочка сообщений шифруется так же, как одно сообщение целиком.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["config", "preset", "keysheet", "seed", "passphrase", "plugboard"]
+    )]
+    resume: Option<String>,
+
+    /// Ключевая таблица, из которой берется суточная установка
+    #[arg(short, long, value_name = "FILE", conflicts_with_all = ["config", "preset"])]
+    keysheet: Option<String>,
+
+    /// Дата суточной установки в формате YYYY-MM-DD [default: сегодня]
+    #[arg(long, value_name = "DATE", requires = "keysheet")]
+    date: Option<Date>,
+
+    /// Историческая модель Энигмы (I, M3, M4) вместо случайной машины
+    ///
+    /// Шифруются только заглавные латинские буквы A-Z,
+    /// конфигурация сохраняется только по --save-config.
+    #[arg(short, long, value_name = "MODEL", conflicts_with = "config")]
+    preset: Option<Model>,
+
+    /// Рефлектор исторической модели (A, B, C, B-thin, C-thin)
+    #[arg(long, value_name = "NAME", default_value = "B", requires = "preset")]
+    reflector: String,
+
+    /// Роторы исторической модели слева направо
+    #[arg(long, value_name = "NAMES", default_value = "I II III", requires = "preset")]
+    rotors: String,
+
+    /// Кольцевые установки роторов исторической модели слева направо [default: AAA]
+    #[arg(long, value_name = "LETTERS", requires = "preset")]
+    rings: Option<String>,
+
+    /// Начальные позиции роторов исторической модели слева направо [default: AAA]
+    #[arg(long, value_name = "LETTERS", requires = "preset")]
+    positions: Option<String>,
 }
 
-fn main() {
-    let cli = Cli::parse();
+#[derive(Args)]
+struct CipherArgs {
+    /// Имя обрабатываемого файла, "-" для стандартного ввода
+    filename: String,
 
-    let mut enigma = get_enigma(cli.config.clone(), cli.rotors_num, cli.with_commutator)
-        .unwrap_or_else(|e| {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        });
+    #[command(flatten, next_help_heading = "Установка машины")]
+    machine: MachineArgs,
 
-    let file_data = read_file_data(&cli.filename).unwrap_or_else(|e| {
-        eprintln!("{}", e);
-        std::process::exit(1);
-    });
+    /// Имя выходного файла, "-" для стандартного вывода
+    /// [default: e<FILENAME> при шифровании, <FILENAME> без префикса "e" или d<FILENAME> при расшифровании]
+    #[arg(short, long, value_name = "FILE")]
+    out: Option<String>,
 
-    let encrypted_data = enigma.encrypt(&file_data).unwrap_or_else(|e| {
-        eprintln!("Pos: {}; Error: {}", e.0, e.1);
-        std::process::exit(1);
-    });
+    /// Сохранить конфигурацию машины в файл
+    ///
+    /// Обязательно для случайной машины без --seed и --passphrase:
+    /// без конфигурации зашифрованное ею нельзя расшифровать.
+    #[arg(long, value_name = "FILE")]
+    save_config: Option<String>,
+
+    /// Перезаписать существующие файлы конфигурации и состояния
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        action = ArgAction::SetTrue
+    )]
+    force: bool,
+
+    /// Сохранить состояние машины после шифрования (формат как у конфигурации)
+    #[arg(long, value_name = "FILE")]
+    save_state: Option<String>,
+
+    /// Смещение в обрабатываемом файле в байтах, с которого начинается обработка
+    ///
+    /// Роторы устанавливаются в положение после обработки первых OFFSET байт,
+    /// что позволяет расшифровать часть файла, не обрабатывая его начало.
+    #[arg(long, value_name = "BYTES", default_value_t = 0, conflicts_with_all = ["text", "resume"])]
+    offset: u64,
 
-    write_encrypted_data(
-        match cli.out {
-            Some(filename) => filename,
-            None => add_e_prefix(&cli.filename),
-        },
-        &encrypted_data,
-    )
+    /// Количество обрабатываемых байт [default: до конца файла]
+    #[arg(long, value_name = "BYTES", conflicts_with = "text")]
+    length: Option<u64>,
+
+    /// Количество потоков шифрования
+    ///
+    /// Данные читаются большими блоками, каждый блок делится между потоками,
+    /// результат совпадает с однопоточным шифрованием.
+    #[arg(short = 't', long, value_name = "NUM", default_value_t = 1, conflicts_with = "text")]
+    threads: usize,
+
+    /// Использовать ключ сообщения (индикатор)
+    ///
+    /// При шифровании выбираются случайные начальные позиции роторов, они шифруются
+    /// установкой машины и записываются в начало файла. При расшифровании
+    /// позиции восстанавливаются из начала файла.
+    #[arg(
+        long,
+        default_value_t = false,
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["offset", "length", "resume"]
+    )]
+    indicator: bool,
+
+    /// Вывести таблицу прохождения каждого символа через машину
+    ///
+    /// Для каждого символа показываются позиции роторов до и после поворота,
+    /// значения после коммутатора, каждого ротора в прямом направлении, рефлектора
+    /// и каждого ротора в обратном направлении. Файл обрабатывается целиком в памяти.
+    #[arg(
+        long,
+        default_value_t = false,
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["threads", "indicator"]
+    )]
+    trace: bool,
+}
+
+#[derive(Args)]
+struct GenconfigArgs {
+    #[command(flatten, next_help_heading = "Установка машины")]
+    machine: MachineArgs,
+
+    /// Имя конфигурационного файла [default: enigma.conf или enigma.toml]
+    #[arg(short, long, value_name = "FILE")]
+    out: Option<String>,
+
+    /// Перезаписать существующий файл конфигурации
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        action = ArgAction::SetTrue
+    )]
+    force: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Шифрование файла
+    Encrypt(CipherArgs),
+
+    /// Расшифрование файла
+    ///
+    /// Машина устанавливается так же, как при шифровании, случайная машина
+    /// не допускается. Индикатор (--indicator)
+    /// читается из начала файла, в текстовом режиме разбивка на группы убирается.
+    Decrypt(CipherArgs),
+
+    /// Создание конфигурационного файла машины без шифрования
+    Genconfig(GenconfigArgs),
+
+    /// Взлом шифртекста без известного открытого текста
+    ///
+    /// Перебираются порядок и начальные позиции роторов (кольцевые установки
+    /// считаются равными первому символу алфавита), затем подбирается коммутатор.
+    Crack(CrackArgs),
+
+    /// Создание ключевой таблицы суточных установок исторической модели
+    ///
+    /// Таблица записывается в машиночитаемом формате TOML (для --keysheet)
+    /// и в текстовом виде для печати.
+    Keysheet(KeysheetArgs),
+
+    /// Интерактивная машина: ламповая панель, окошки роторов и коммутатор
+    ///
+    /// Нажатые клавиши зажигают лампы, роторы можно устанавливать и поворачивать
+    /// вручную, а кабели коммутатора - переставлять на ходу.
+    Interactive(InteractiveArgs),
+}
+
+#[derive(Args)]
+struct InteractiveArgs {
+    /// Историческая модель Энигмы (I, M3, M4)
+    #[arg(short, long, value_name = "MODEL", default_value = "I", conflicts_with = "config")]
+    model: Model,
+
+    /// Рефлектор исторической модели
+    #[arg(long, value_name = "NAME", default_value = "B", conflicts_with = "config")]
+    reflector: String,
+
+    /// Роторы исторической модели слева направо
+    #[arg(long, value_name = "NAMES", default_value = "I II III", conflicts_with = "config")]
+    rotors: String,
+
+    /// Кольцевые установки роторов слева направо [default: AAA]
+    #[arg(long, value_name = "LETTERS", conflicts_with = "config")]
+    rings: Option<String>,
+
+    /// Начальные позиции роторов слева направо [default: AAA]
+    #[arg(long, value_name = "LETTERS", conflicts_with = "config")]
+    positions: Option<String>,
+
+    /// Коммутатор из заданных пар символов, например "AV BS CG"
+    #[arg(long, value_name = "PAIRS")]
+    plugboard: Option<String>,
+
+    /// Конфигурационный файл машины с символьным алфавитом вместо исторической модели
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<String>,
+
+    /// Формат конфигурационного файла
+    #[arg(long, value_name = "FORMAT", value_enum, default_value_t = ConfigFormat::Bin)]
+    config_format: ConfigFormat,
+}
+
+#[derive(Args)]
+struct KeysheetArgs {
+    /// Историческая модель Энигмы (I, M3, M4)
+    #[arg(short, long, value_name = "MODEL", default_value = "I")]
+    model: Model,
+
+    /// Дата первого дня таблицы в формате YYYY-MM-DD [default: сегодня]
+    #[arg(long, value_name = "DATE")]
+    start: Option<Date>,
+
+    /// Количество дней
+    #[arg(long, value_name = "NUM", default_value_t = 31)]
+    days: u32,
+
+    /// Зерно генератора: одинаковое зерно дает одинаковую таблицу [default: случайное]
+    #[arg(long, value_name = "NUM")]
+    seed: Option<u64>,
+
+    /// Имя файла таблицы в формате TOML
+    #[arg(short, long, value_name = "FILE", default_value = "keysheet.toml")]
+    out: String,
+
+    /// Имя файла таблицы для печати
+    #[arg(long, value_name = "FILE", default_value = "keysheet.txt")]
+    printable: String,
+
+    /// Перезаписать существующие файлы таблицы
+    #[arg(
+        short,
+        long,
+        default_value_t = false,
+        action = ArgAction::SetTrue
+    )]
+    force: bool,
+}
+
+#[derive(Args)]
+struct CrackArgs {
+    /// Имя файла с шифртекстом, "-" для стандартного ввода
+    filename: String,
+
+    /// Язык открытого текста
+    #[arg(short, long, value_name = "LANGUAGE", value_enum, default_value_t = TextLanguage::English)]
+    language: TextLanguage,
+
+    /// Историческая модель Энигмы (I, M3), роторы которой перебираются
+    #[arg(short, long, value_name = "MODEL", default_value = "I", conflicts_with = "config")]
+    model: Model,
+
+    /// Рефлектор исторической модели
+    #[arg(long, value_name = "NAME", default_value = "B", conflicts_with = "config")]
+    reflector: String,
+
+    /// Конфигурационный файл с известными роторами и рефлектором вместо исторической модели
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<String>,
+
+    /// Формат конфигурационного файла
+    #[arg(long, value_name = "FORMAT", value_enum, default_value_t = ConfigFormat::Bin)]
+    config_format: ConfigFormat,
+
+    /// Наибольшее количество соединений коммутатора
+    #[arg(long, value_name = "NUM", default_value_t = 10)]
+    max_plugs: usize,
+}
+
+impl MachineArgs {
+    fn text_alphabet(&self) -> TextAlphabet {
+        match self.alphabet {
+            AlphabetName::Latin => TextAlphabet::Latin,
+            AlphabetName::Russian => TextAlphabet::Russian,
+            AlphabetName::RussianYo => TextAlphabet::RussianYo,
+            AlphabetName::Custom => TextAlphabet::Custom(self.symbols.clone().unwrap_or_default()),
+        }
+    }
+
+    // Случайную машину можно восстановить только из конфигурации
+    fn is_random(&self) -> bool {
+        self.config.is_none()
+            && self.resume.is_none()
+            && self.preset.is_none()
+            && self.keysheet.is_none()
+            && self.seed.is_none()
+            && self.passphrase.is_none()
+    }
+
+    fn default_config_filename(&self) -> &'static str {
+        match self.config_format {
+            ConfigFormat::Bin => "enigma.conf",
+            ConfigFormat::Toml => "enigma.toml",
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match &cli.command {
+        Command::Encrypt(args) => cipher(args, false),
+        Command::Decrypt(args) => cipher(args, true),
+        Command::Genconfig(args) => genconfig(args),
+        Command::Crack(args) => crack(args),
+        Command::Keysheet(args) => keysheet(args),
+        Command::Interactive(args) => interactive(args),
+    }
     .unwrap_or_else(|e| {
         eprintln!("{}", e);
         std::process::exit(1);
     });
+}
+
+fn cipher(args: &CipherArgs, decrypt: bool) -> io::Result<()> {
+    // Случайной машиной нельзя расшифровать, а зашифрованное ею без
+    // сохраненной конфигурации не расшифровать никогда
+    if args.machine.is_random() && decrypt {
+        return Err(Error::new(
+            io::ErrorKind::InvalidInput,
+            "Для расшифрования нужна установка машины: --config, --preset, --keysheet, --resume, --seed или --passphrase",
+        ));
+    }
+    if args.machine.is_random() && args.save_config.is_none() {
+        return Err(Error::new(
+            io::ErrorKind::InvalidInput,
+            "Ключ случайной машины будет потерян: укажите --save-config или установку машины",
+        ));
+    }
+    if let Some(config_filename) = &args.save_config {
+        check_overwrite(config_filename, args.force)?;
+    }
+    if let Some(state_filename) = &args.save_state {
+        check_overwrite(state_filename, args.force)?;
+    }
 
-    if cli.config.is_none() {
-        save_config(&enigma).unwrap_or_else(|e| {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        });
+    let out_filename = match &args.out {
+        Some(filename) => filename.clone(),
+        None if args.filename == STDIO_NAME => STDIO_NAME.to_string(),
+        None if decrypt => decrypted_filename(&args.filename),
+        None => add_prefix(&args.filename, "e"),
+    };
+
+    if args.machine.text {
+        let mut enigma = get_enigma(&args.machine, &args.machine.text_alphabet().symbols())?;
+        encrypt_text(&mut enigma, args, decrypt, &out_filename)?;
+        save_machine(&enigma, args, &out_filename)
+    } else {
+        let mut enigma = get_enigma(&args.machine, &(0..=255).collect::<Vec<u8>>())?;
+        encrypt_stream(&mut enigma, args, decrypt, &out_filename)?;
+        save_machine(&enigma, args, &out_filename)
     }
 }
 
-fn get_enigma(
-    cfg_filename: Option<String>,
-    rotors_num: u8,
-    wirh_commutator: bool,
-) -> io::Result<Enigma<u8>> {
-    let enigma: Enigma<u8>;
-    if let Some(cfg_filename) = &cfg_filename {
+fn genconfig(args: &GenconfigArgs) -> io::Result<()> {
+    let filename = args.out.as_deref().unwrap_or(args.machine.default_config_filename());
+    check_overwrite(filename, args.force)?;
+
+    if args.machine.text {
+        let enigma = get_enigma(&args.machine, &args.machine.text_alphabet().symbols())?;
+        save_config(&enigma, args.machine.config_format, filename)?;
+    } else {
+        let enigma = get_enigma(&args.machine, &(0..=255).collect::<Vec<u8>>())?;
+        save_config(&enigma, args.machine.config_format, filename)?;
+    }
+
+    println!("Конфигурация Энигмы сохранена в файл {}", filename);
+    Ok(())
+}
+
+// Состояние и конфигурация сохраняются только по запросу
+fn save_machine<T: Symbol + ConfigSymbol>(enigma: &Enigma<T>, args: &CipherArgs, out_filename: &str) -> io::Result<()> {
+    let format = args.machine.config_format;
+
+    if let Some(state_filename) = &args.save_state {
+        let mut file = File::create(state_filename)?;
+        let state = enigma
+            .get_state()
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+        match format {
+            ConfigFormat::Bin => BinConfigSerializer::save_state(&mut file, &state)?,
+            ConfigFormat::Toml => TomlConfigSerializer::save_state(&mut file, &state)?,
+        }
+    }
+
+    let Some(config_filename) = &args.save_config else {
+        return Ok(());
+    };
+    save_config(enigma, format, config_filename)?;
+
+    // Стандартный вывод может быть занят зашифрованными данными
+    let message = format!("Конфигурация Энигмы сохранена в файл {}", config_filename);
+    if out_filename == STDIO_NAME {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+
+    Ok(())
+}
+
+// Без предустановки, ключевой таблицы и конфигурации создается случайная машина
+// с алфавитом `alphabet`
+fn get_enigma<T>(cli: &MachineArgs, alphabet: &[T]) -> io::Result<Enigma<T>>
+where
+    T: Symbol + ConfigSymbol + From<u8> + TryFrom<char>,
+{
+    let mut enigma: Enigma<T>;
+    if let Some(state_filename) = &cli.resume {
+        let mut state_file = File::open(state_filename)
+            .map_err(|err| io::Error::new(err.kind(), format!("Ошибка открытия файла состояния '{}': {}", state_filename, err)))?;
+
+        let state = match cli.config_format {
+            ConfigFormat::Bin => BinConfigSerializer::get_state(&mut state_file)?,
+            ConfigFormat::Toml => TomlConfigSerializer::get_state(&mut state_file)?,
+        };
+
+        enigma = Enigma::from_state(&state)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+    } else if let Some(model) = cli.preset {
+        let rotors_names: Vec<&str> = cli.rotors.split_whitespace().collect();
+        let default_settings = "A".repeat(rotors_names.len());
+
+        enigma = Enigma::from_preset(
+            model,
+            &cli.reflector,
+            &rotors_names,
+            cli.rings.as_deref().unwrap_or(&default_settings),
+            cli.positions.as_deref().unwrap_or(&default_settings),
+            "",
+        )
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+    } else if let Some(sheet_filename) = &cli.keysheet {
+        let mut sheet_file = File::open(sheet_filename)
+            .map_err(|err| io::Error::new(err.kind(), format!("Ошибка открытия ключевой таблицы '{}': {}", sheet_filename, err)))?;
+        let sheet = KeySheet::load(&mut sheet_file)?;
+
+        enigma = sheet
+            .enigma(cli.date.unwrap_or_else(Date::today))
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+    } else if let Some(cfg_filename) = &cli.config {
         let mut cfg_file = File::open(cfg_filename)
             .map_err(|err| io::Error::new(err.kind(), format!("Ошибка открытия кофигурационного файла '{}': {}", cfg_filename, err)))?;
 
-        let (commutator_cfg, reflector_cfg, rotors_cfgs) = BinConfigSerializer::get_configs(&mut cfg_file)?;
+        let config = match cli.config_format {
+            ConfigFormat::Bin => BinConfigSerializer::get_configs(&mut cfg_file)?,
+            ConfigFormat::Toml => TomlConfigSerializer::get_configs(&mut cfg_file)?,
+        };
 
-        enigma = Enigma::from_config(
-            commutator_cfg.as_ref().map(|v| v.as_slice()),
-            &reflector_cfg,
-            &rotors_cfgs,
-        )
-        .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+        enigma = Enigma::from_config(&config)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
     } else {
-        enigma =
-            Enigma::from_alphabet(&(0..=255).collect::<Vec<u8>>(), rotors_num, wirh_commutator)
-                .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+        enigma = match (cli.seed, &cli.passphrase) {
+            (Some(seed), _) => Enigma::from_seed(alphabet, cli.rotors_num, cli.with_commutator, seed),
+            (_, Some(passphrase)) => Enigma::from_passphrase(alphabet, cli.rotors_num, cli.with_commutator, passphrase),
+            _ => Enigma::from_alphabet(alphabet, cli.rotors_num, cli.with_commutator),
+        }
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+
+    if let Some(pairs) = &cli.plugboard {
+        let plugboard = Plugboard::from_notation(pairs)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+        enigma
+            .set_plugboard(Some(plugboard))
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
     }
+    enigma.set_unknown_symbols(cli.unknown.into());
 
     Ok(enigma)
 }
 
-fn read_file_data(filename: &str) -> io::Result<Vec<u8>> {
-    let mut file_data = Vec::new();
+fn open_input(filename: &str, offset: u64) -> io::Result<Box<dyn Read>> {
+    if filename == STDIO_NAME {
+        // Стандартный ввод нельзя перемотать, поэтому начало вычитывается
+        let mut stdin = io::stdin().lock();
+        io::copy(&mut (&mut stdin).take(offset), &mut io::sink())?;
+        return Ok(Box::new(stdin));
+    }
+
     let mut file = OpenOptions::new()
         .read(true)
         .open(filename)
         .map_err(|err| io::Error::new(err.kind(), format!("Ошибка открытия шифруемого файла '{}': {}", filename, err)))?;
+    file.seek(SeekFrom::Start(offset))?;
+
+    Ok(Box::new(file))
+}
+
+fn open_output(filename: &str) -> io::Result<Box<dyn Write>> {
+    if filename == STDIO_NAME {
+        return Ok(Box::new(io::stdout().lock()));
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(filename)?;
+
+    Ok(Box::new(file))
+}
+
+fn encrypt_stream(enigma: &mut Enigma<u8>, cli: &CipherArgs, decrypt: bool, out_filename: &str) -> io::Result<()> {
+    let offset = usize::try_from(cli.offset)
+        .map_err(|_| Error::new(io::ErrorKind::InvalidInput, "Слишком большое смещение"))?;
+
+    // Символы не из алфавита не вращают роторы, поэтому без ошибок на них
+    // положение роторов зависит от содержимого пропускаемого начала
+    // Без смещения роторы остаются на месте, в том числе после --resume
+    let input = if offset == 0 {
+        open_input(&cli.filename, 0)?
+    } else if cli.machine.unknown == UnknownPolicy::Error {
+        enigma.seek(offset);
+        open_input(&cli.filename, cli.offset)?
+    } else {
+        enigma.reset();
+        let mut input = open_input(&cli.filename, 0)?;
+        io::copy(&mut enigma.reader((&mut input).take(cli.offset)), &mut io::sink())?;
+        input
+    };
+    let mut input = BufReader::new(input.take(cli.length.unwrap_or(u64::MAX)));
+    let mut output = BufWriter::new(open_output(out_filename)?);
+
+    if cli.indicator && decrypt {
+        let mut indicator = vec![0; enigma.positions().len()];
+        input
+            .read_exact(&mut indicator)
+            .map_err(|_| Error::new(io::ErrorKind::InvalidData, EnigmaError::MissingIndicator))?;
+        enigma
+            .decrypt_indicator(&indicator)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+    } else if cli.indicator {
+        let message_key = enigma.random_message_key();
+        let indicator = enigma
+            .encrypt_indicator(&message_key)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+        output.write_all(&indicator)?;
+    }
+
+    if cli.trace {
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+        let (crypto, traces) = enigma
+            .encrypt_traced(&data)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
 
+        print_trace(&traces, |&b| if b.is_ascii_graphic() { (b as char).to_string() } else { format!("0x{:02x}", b) }, out_filename == STDIO_NAME);
+        output.write_all(&crypto)?;
+    } else if cli.threads > 1 {
+        encrypt_blocks(enigma, input, &mut output, cli.threads)?;
+    } else {
+        io::copy(&mut enigma.reader(input), &mut output)?;
+    }
+    output.flush()?;
+
+    if out_filename != STDIO_NAME {
+        let data = if decrypt { "Расшифрованные" } else { "Зашифрованные" };
+        println!("{} данные сохранены в файл {}", data, out_filename);
+    }
 
-    let _bytes = file.read_to_end(&mut file_data);
-    Ok(file_data)
+    Ok(())
 }
 
-fn add_e_prefix(filename: &str) -> String {
-    if let Some((dir, file)) = filename.rsplit_once('\\') {
-        format!("{}\\e{}", dir, file)
-    } else if let Some((dir, file)) = filename.rsplit_once('/') {
-        format!("{}/e{}", dir, file)
+fn encrypt_text(enigma: &mut Enigma<char>, cli: &CipherArgs, decrypt: bool, out_filename: &str) -> io::Result<()> {
+    let mut input = String::new();
+    open_input(&cli.filename, 0)?.read_to_string(&mut input)?;
+    let symbols = enigma.normalize_text(&input);
+
+    let output = if cli.trace {
+        enigma.encrypt_traced(&symbols).map(|(output, traces)| {
+            print_trace(&traces, |symb| symb.to_string(), out_filename == STDIO_NAME);
+            if decrypt {
+                output.into_iter().collect()
+            } else {
+                text::to_groups(&output)
+            }
+        })
+    } else if decrypt {
+        let plaintext = if cli.indicator {
+            enigma.decrypt_with_indicator(&symbols)
+        } else {
+            enigma.decrypt(&symbols)
+        };
+        plaintext.map(|symbols| symbols.into_iter().collect::<String>())
     } else {
-        format!("e{}", filename)
+        let crypto = if cli.indicator {
+            let message_key = enigma.random_message_key();
+            enigma.encrypt_with_indicator(&symbols, &message_key)
+        } else {
+            enigma.encrypt(&symbols)
+        };
+        crypto.map(|symbols| text::to_groups(&symbols))
+    }
+    .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut out = open_output(out_filename)?;
+    writeln!(out, "{}", output)?;
+    out.flush()?;
+
+    if out_filename != STDIO_NAME {
+        let data = if decrypt { "Расшифрованные" } else { "Зашифрованные" };
+        println!("{} данные сохранены в файл {}", data, out_filename);
+    }
+
+    Ok(())
+}
+
+// Таблица пишется в стандартный поток ошибок, если стандартный вывод занят
+// зашифрованными данными. Позиции роторов показываются слева направо, как в окошках
+fn print_trace<T>(traces: &[SymbolTrace<T>], show: impl Fn(&T) -> String, stdout_busy: bool) {
+    let windows = |positions: &[T]| {
+        let windows: Vec<String> = positions.iter().rev().map(&show).collect();
+        if windows.iter().all(|w| w.chars().count() == 1) {
+            windows.concat()
+        } else {
+            windows.join(" ")
+        }
+    };
+    let path = |symbols: &[T]| symbols.iter().map(&show).collect::<Vec<_>>().join(" ");
+
+    // Столбцы входного колеса показываются, только если оно есть в машине
+    let with_entry = traces.iter().any(|trace| trace.entry_in.is_some());
+    let entry = |symb: &Option<T>| symb.as_ref().map(&show).unwrap_or_default();
+
+    let mut header = vec!["№", "Вход", "Роторы", "Коммутатор"];
+    if with_entry {
+        header.push("ETW →");
+    }
+    header.extend(["Роторы →", "Рефлектор", "Роторы ←"]);
+    if with_entry {
+        header.push("ETW ←");
+    }
+    header.push("Выход");
+
+    let mut rows = vec![header.into_iter().map(String::from).collect::<Vec<_>>()];
+    for trace in traces {
+        let mut row = vec![
+            (trace.index + 1).to_string(),
+            show(&trace.input),
+            format!("{} → {}", windows(&trace.positions_before), windows(&trace.positions_after)),
+            show(&trace.plugboard_in),
+        ];
+        if with_entry {
+            row.push(entry(&trace.entry_in));
+        }
+        row.extend([path(&trace.forward), show(&trace.reflector), path(&trace.backward)]);
+        if with_entry {
+            row.push(entry(&trace.entry_out));
+        }
+        row.push(show(&trace.output));
+        rows.push(row);
+    }
+
+    let mut widths = vec![0; rows[0].len()];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    for row in &rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        if stdout_busy {
+            eprintln!("{}", line.trim_end());
+        } else {
+            println!("{}", line.trim_end());
+        }
+    }
+}
+
+fn encrypt_blocks(enigma: &mut Enigma<u8>, mut input: impl Read, output: &mut impl Write, threads: usize) -> io::Result<()> {
+    let mut block = Vec::new();
+    let mut processed = 0;
+
+    loop {
+        block.clear();
+        (&mut input).take(PARALLEL_BLOCK_SIZE).read_to_end(&mut block)?;
+        if block.is_empty() {
+            return Ok(());
+        }
+
+        let len = enigma
+            .encrypt_in_place_parallel(&mut block, threads)
+            .map_err(|e| match e {
+                EnigmaError::SymbolNotInAlphabet(i) => EnigmaError::SymbolNotInAlphabet(processed + i),
+                e => e,
+            })
+            .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+        processed += block.len();
+
+        output.write_all(&block[..len])?;
     }
 }
 
-fn write_encrypted_data(filename: String, encrypted_data: &[u8]) -> io::Result<()> {
-    println!("Зашифрованные данные сохранены в файл {}", filename);
+fn crack(args: &CrackArgs) -> io::Result<()> {
+    let language = Language::from(args.language);
+
+    let mut text = String::new();
+    open_input(&args.filename, 0)?.read_to_string(&mut text)?;
+    let alphabet = language.alphabet();
+    let ciphertext: Vec<char> = text
+        .chars()
+        .map(|c| language.normalize(c))
+        .filter(|c| alphabet.contains(c))
+        .collect();
+
+    let model = language.model();
+    let mut cracker = match &args.config {
+        Some(cfg_filename) => {
+            let mut cfg_file = File::open(cfg_filename)
+                .map_err(|err| io::Error::new(err.kind(), format!("Ошибка открытия кофигурационного файла '{}': {}", cfg_filename, err)))?;
+            let config: EnigmaConfig<char> = match args.config_format {
+                ConfigFormat::Bin => BinConfigSerializer::get_configs(&mut cfg_file)?,
+                ConfigFormat::Toml => TomlConfigSerializer::get_configs(&mut cfg_file)?,
+            };
+            Cracker::from_config(&config, &model)
+        }
+        None => Cracker::from_preset(args.model, &args.reflector, &model),
+    }
+    .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+    cracker.set_max_plugs(args.max_plugs);
+
+    let result = cracker
+        .crack(&ciphertext)
+        .map_err(|e| Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let positions: String = result.config.rotors.iter().rev().map(|r| r.position).collect();
+    let plugs: Vec<String> = result
+        .config
+        .commutator
+        .unwrap_or_default()
+        .chunks(2)
+        .map(|pair| pair.iter().collect())
+        .collect();
+
+    println!("Роторы: {}", result.rotors.join(" "));
+    println!("Начальные позиции: {}", positions);
+    println!("Коммутатор: {}", plugs.join(" "));
+    println!("{}", result.plaintext.iter().collect::<String>());
+
+    Ok(())
+}
+
+fn keysheet(args: &KeysheetArgs) -> io::Result<()> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let sheet = KeySheet::generate(args.model, args.start.unwrap_or_else(Date::today), args.days, seed);
+
+    check_overwrite(&args.out, args.force)?;
+    check_overwrite(&args.printable, args.force)?;
 
     let mut file = OpenOptions::new()
         .create(true)
         .write(true)
         .truncate(true)
-        .open(filename)?;
+        .open(&args.out)?;
+    sheet.save(&mut file)?;
 
-    file.write_all(&encrypted_data)?;
+    fs::write(&args.printable, sheet.to_printable())?;
+
+    println!("Ключевая таблица сохранена в файлы {} и {} (зерно {})", args.out, args.printable, seed);
     Ok(())
 }
 
-fn save_config(enigma: &Enigma<u8>) -> io::Result<()> {
-    print!("Введите файл в который сохранить конфигурацию Энигмы (./enigma.conf): ");
-    io::stdout().flush()?;
+fn interactive(args: &InteractiveArgs) -> io::Result<()> {
+    let mut enigma: Enigma<char> = match &args.config {
+        Some(cfg_filename) => {
+            let mut cfg_file = File::open(cfg_filename)
+                .map_err(|err| io::Error::new(err.kind(), format!("Ошибка открытия кофигурационного файла '{}': {}", cfg_filename, err)))?;
+            let config = match args.config_format {
+                ConfigFormat::Bin => BinConfigSerializer::get_configs(&mut cfg_file)?,
+                ConfigFormat::Toml => TomlConfigSerializer::get_configs(&mut cfg_file)?,
+            };
+            Enigma::from_config(&config)
+        }
+        None => {
+            let rotors_names: Vec<&str> = args.rotors.split_whitespace().collect();
+            let default_settings = "A".repeat(rotors_names.len());
 
-    let mut filename = String::new();
-    io::stdin().read_line(&mut filename)?;
+            Enigma::from_preset(
+                args.model,
+                &args.reflector,
+                &rotors_names,
+                args.rings.as_deref().unwrap_or(&default_settings),
+                args.positions.as_deref().unwrap_or(&default_settings),
+                "",
+            )
+        }
+    }
+    .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
 
-    let filename = filename.trim();
+    if let Some(pairs) = &args.plugboard {
+        let plugboard = Plugboard::from_notation(pairs)
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+        enigma
+            .set_plugboard(Some(plugboard))
+            .map_err(|e| Error::new(io::ErrorKind::InvalidInput, e))?;
+    }
 
-    let filename = if filename.is_empty() {
-        "./enigma.conf".to_string()
-    } else {
-        filename.to_string()
-    };
+    repl::run(&mut enigma, io::stdin().lock(), &mut io::stdout().lock())
+}
+
+fn split_filename(filename: &str) -> (&str, &str) {
+    match filename.rfind(['\\', '/']) {
+        Some(i) => filename.split_at(i + 1),
+        None => ("", filename),
+    }
+}
 
-    println!("Сохранение в файл: {}", filename);
+fn add_prefix(filename: &str, prefix: &str) -> String {
+    let (dir, file) = split_filename(filename);
+    format!("{}{}{}", dir, prefix, file)
+}
 
+// Расшифрованный файл получает имя исходного: префикс "e" снимается,
+// а файл без префикса получает префикс "d"
+fn decrypted_filename(filename: &str) -> String {
+    let (dir, file) = split_filename(filename);
+    match file.strip_prefix('e') {
+        Some(name) if !name.is_empty() => format!("{}{}", dir, name),
+        _ => add_prefix(filename, "d"),
+    }
+}
+
+// Конфигурации, состояния и ключевые таблицы - это ключи, поэтому существующий файл
+// без --force не заменяется
+fn check_overwrite(filename: &str, force: bool) -> io::Result<()> {
+    if !force && fs::exists(filename)? {
+        return Err(Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("Файл '{}' уже существует, для перезаписи укажите --force", filename),
+        ));
+    }
+
+    Ok(())
+}
+
+fn save_config<T: Symbol + ConfigSymbol>(enigma: &Enigma<T>, format: ConfigFormat, filename: &str) -> io::Result<()> {
     let mut file = OpenOptions::new()
         .create(true)
         .write(true)
         .truncate(true)
         .open(filename)?;
 
-    let (com_cfg, ref_cfg, rotors_cfgs) = enigma.get_config();
-    BinConfigSerializer::save_configs(
-        &mut file,
-        com_cfg.as_ref().map(|v| v.as_slice()),
-        &ref_cfg,
-        &rotors_cfgs,
-    )?;
+    let config = enigma.get_config();
+    match format {
+        ConfigFormat::Bin => BinConfigSerializer::save_configs(&mut file, &config)?,
+        ConfigFormat::Toml => TomlConfigSerializer::save_configs(&mut file, &config)?,
+    }
 
     Ok(())
 }
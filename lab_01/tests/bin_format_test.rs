@@ -0,0 +1,95 @@
+use std::{
+    fs::{self, File},
+    io::ErrorKind,
+};
+
+use enigma::{
+    Enigma, Mechanics,
+    cfg::{
+        BinConfigSerializer, ConfigSerializer, EnigmaConfig,
+        bin::{FORMAT_VERSION, MAGIC},
+    },
+};
+
+mod common;
+
+fn config() -> EnigmaConfig<u8> {
+    common::byte_enigma(3, true).get_config()
+}
+
+fn save(config: &EnigmaConfig<u8>, filename: &str) -> Vec<u8> {
+    let path = common::temp_path(filename);
+    let mut file = File::create(&path).expect("Can't create config file");
+    BinConfigSerializer::save_configs(&mut file, config).expect("Can't save config");
+
+    let data = fs::read(&path).expect("Can't read config file");
+    fs::remove_file(&path).ok();
+    data
+}
+
+fn load(data: &[u8], filename: &str) -> std::io::Result<EnigmaConfig<u8>> {
+    let path = common::temp_path(filename);
+    fs::write(&path, data).expect("Can't write config file");
+
+    let mut file = File::open(&path).expect("Can't open config file");
+    let loaded = BinConfigSerializer::get_configs(&mut file);
+    fs::remove_file(&path).ok();
+    loaded
+}
+
+#[test]
+fn test_header() {
+    let config = config();
+    let data = save(&config, "header.conf");
+
+    assert_eq!(data[..4], MAGIC);
+    assert_eq!(data[4], FORMAT_VERSION);
+    assert_eq!(data[5], 1);
+    assert_eq!(data[6..10], 256u32.to_le_bytes());
+    assert_eq!(data[10] & 1, 1);
+
+    let loaded = load(&data, "header_load.conf").expect("Can't load config");
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn test_corrupted_config_rejected() {
+    let data = save(&config(), "corrupted.conf");
+
+    let mut flipped = data.clone();
+    flipped[100] ^= 0x01;
+    let err = load(&flipped, "flipped.conf").expect_err("Corrupted config loaded");
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let truncated = &data[..data.len() - 10];
+    let err = load(truncated, "truncated.conf").expect_err("Truncated config loaded");
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let mut future = data.clone();
+    future[4] = FORMAT_VERSION + 1;
+    assert!(load(&future, "future.conf").is_err());
+}
+
+#[test]
+fn test_legacy_headerless_config() {
+    // Конфигурация и шифротекст записаны версией 0.1 (`enigma -m`)
+    let data = include_bytes!("data/v0_1.conf");
+    let message = include_bytes!("data/v0_1_message.txt");
+    let crypto = include_bytes!("data/v0_1_message.enc");
+
+    let loaded = load(data, "legacy.conf").expect("Can't load legacy config");
+    assert_eq!(loaded.mechanics, Mechanics::Legacy);
+    assert_eq!(loaded.alphabet, (0..=255).collect::<Vec<u8>>());
+    assert!(loaded.commutator.is_some());
+    assert_eq!(loaded.rotors.len(), 3);
+
+    let mut e = Enigma::from_config(&loaded).expect("Incorrect config");
+    assert_eq!(
+        e.decrypt(crypto).expect("Symbol in alphabet not founded"),
+        message
+    );
+
+    // Файл без последнего ротора обрезан, а не прочитан по другой раскладке
+    let err = load(&data[..data.len() - 1], "legacy.conf").expect_err("Truncated file loaded");
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
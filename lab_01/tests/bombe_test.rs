@@ -0,0 +1,104 @@
+use enigma::{
+    Enigma, EnigmaError, Plugboard,
+    analysis::bombe::{Bombe, Menu},
+    preset::Model,
+};
+
+const PLUGBOARD: &str = "AV BS CG DL FU HZ IN KM OW RX";
+
+fn encrypt_str(e: &mut Enigma<u8>, msg: &str) -> String {
+    let crypto = e
+        .encrypt(msg.as_bytes())
+        .expect("Symbol in alphabet not founded");
+    String::from_utf8(crypto).expect("Not a letter")
+}
+
+fn stecker_of(letter: char) -> char {
+    PLUGBOARD
+        .split_whitespace()
+        .find_map(|pair| {
+            let (a, b) = (pair.as_bytes()[0] as char, pair.as_bytes()[1] as char);
+            if a == letter {
+                Some(b)
+            } else if b == letter {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(letter)
+}
+
+#[test]
+fn test_bombe_finds_setting() {
+    let mut e: Enigma<u8> =
+        Enigma::from_preset(Model::EnigmaI, "B", &["II", "V", "III"], "AAA", "KDR", "")
+            .expect("Invalid preset");
+    e.set_plugboard(Some(
+        Plugboard::from_notation(PLUGBOARD).expect("Invalid plugboard"),
+    ))
+    .expect("Invalid plugboard");
+
+    let crib = "WETTERVORHERSAGEBISKAYA";
+    let ciphertext = encrypt_str(&mut e, &format!("{}NEBELUNDREGEN", crib));
+
+    let menu = Menu::new(&ciphertext, crib, 0).expect("Invalid crib");
+    assert!(menu.loops() >= 3);
+
+    let bombe = Bombe::new(Model::EnigmaI, "B").expect("Invalid bombe");
+    let stops = bombe
+        .run(&menu, &[["II", "V", "III"]])
+        .expect("Invalid rotors");
+
+    assert!(
+        !stops.is_empty() && stops.len() < 5,
+        "{} stops",
+        stops.len()
+    );
+
+    let stop = stops
+        .iter()
+        .find(|stop| stop.positions == "KDR")
+        .expect("Right setting not found");
+    assert_eq!(stop.rotors, ["II", "V", "III"]);
+    for &(a, b) in &stop.steckers {
+        assert_eq!(stecker_of(a), b, "wrong stecker {}{}", a, b);
+    }
+}
+
+#[test]
+fn test_menu_validation() {
+    assert_eq!(
+        Menu::new("ABC", "ABD", 0).err(),
+        Some(EnigmaError::InvalidCrib(
+            "letter can't be encrypted to itself"
+        ))
+    );
+    assert!(Menu::new("ABC", "XYZW", 0).is_err());
+    assert!(Menu::new("ABC", "XY", 2).is_err());
+    assert!(Menu::new("ABC", "xy", 0).is_err());
+
+    let menu = Menu::new("BCA", "ABC", 0).expect("Invalid crib");
+    assert_eq!(menu.edges().len(), 3);
+    assert_eq!(menu.loops(), 1);
+}
+
+#[test]
+fn test_bombe_rotor_orders() {
+    assert_eq!(
+        Bombe::new(Model::EnigmaI, "B")
+            .expect("Invalid bombe")
+            .rotor_orders()
+            .len(),
+        60
+    );
+    assert_eq!(
+        Bombe::new(Model::M3, "C")
+            .expect("Invalid bombe")
+            .rotor_orders()
+            .len(),
+        336
+    );
+    assert!(Bombe::new(Model::M4, "B-thin").is_err());
+    assert!(Bombe::new(Model::M3, "A").is_err());
+}
@@ -0,0 +1,29 @@
+// Общие заготовки тестов, каждый тестовый файл использует только часть из них
+#![allow(dead_code)]
+
+use std::{
+    env,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use enigma::Enigma;
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub fn byte_enigma(rotors_cnt: u8, with_commutator: bool) -> Enigma<u8> {
+    Enigma::from_alphabet(&(0..=255).collect::<Vec<u8>>(), rotors_cnt, with_commutator)
+        .expect("Incorrect alphabet")
+}
+
+pub fn byte_message() -> Vec<u8> {
+    (0..200_000u32).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+// Тесты выполняются параллельно, а cargo test может быть запущен несколько раз
+// одновременно, поэтому имя временного файла включает номер процесса и счетчик
+pub fn temp_path(name: &str) -> PathBuf {
+    let id = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    env::temp_dir().join(format!("enigma_{}_{}_{}", process::id(), id, name))
+}
@@ -0,0 +1,124 @@
+use std::fs::{self, File};
+
+use enigma::{
+    Component, Enigma, EnigmaError, Plugboard, Symbol,
+    cfg::{
+        BinConfigSerializer, ConfigSerializer, ConfigSymbol, EnigmaConfig, TomlConfigSerializer,
+    },
+};
+
+mod common;
+
+fn roundtrip<T, S>(e: &mut Enigma<T>, msg: &[T], filename: &str)
+where
+    T: Symbol + std::fmt::Debug + ConfigSymbol,
+    S: ConfigSerializer<T>,
+{
+    let path = common::temp_path(filename);
+    let config = e.get_config();
+
+    let mut file = File::create(&path).expect("Can't create config file");
+    S::save_configs(&mut file, &config).expect("Can't save config");
+
+    let mut file = File::open(&path).expect("Can't open config file");
+    let loaded = S::get_configs(&mut file).expect("Can't load config");
+    fs::remove_file(&path).ok();
+
+    assert_eq!(loaded, config);
+
+    e.reset();
+    let crypto = e.encrypt(msg).expect("Symbol in alphabet not founded");
+    let mut loaded_e = Enigma::from_config(&loaded).expect("Incorrect config");
+    let decrypt = loaded_e
+        .decrypt(&crypto)
+        .expect("Symbol in alphabet not founded");
+
+    assert_eq!(decrypt, msg);
+}
+
+fn byte_enigma() -> Enigma<u8> {
+    let mut e = common::byte_enigma(3, false);
+    e.set_plugboard(Some(
+        Plugboard::from_notation("AB CD").expect("Incorrect plugboard"),
+    ))
+    .expect("Incorrect plugboard");
+    e
+}
+
+#[test]
+fn test_bin_config_roundtrip() {
+    roundtrip::<_, BinConfigSerializer>(&mut byte_enigma(), b"HELLO", "bytes.conf");
+}
+
+#[test]
+fn test_toml_config_roundtrip() {
+    roundtrip::<_, TomlConfigSerializer>(&mut byte_enigma(), b"HELLO", "bytes.toml");
+}
+
+#[test]
+fn test_char_config_roundtrip() {
+    let latin: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect();
+    let cyrillic: Vec<char> = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ".chars().collect();
+
+    let mut e = Enigma::from_alphabet(&latin, 3, true).expect("Incorrect alphabet");
+    let msg: Vec<char> = "HELLOWORLD".chars().collect();
+    roundtrip::<_, BinConfigSerializer>(&mut e, &msg, "latin.conf");
+
+    let mut e = Enigma::from_alphabet(&cyrillic, 5, true).expect("Incorrect alphabet");
+    let msg: Vec<char> = "ПРИВЕТМИР".chars().collect();
+    roundtrip::<_, BinConfigSerializer>(&mut e, &msg, "cyrillic.conf");
+    roundtrip::<_, TomlConfigSerializer>(&mut e, &msg, "cyrillic.toml");
+}
+
+#[test]
+fn test_config_validation() {
+    let e = Enigma::from_alphabet(&['A', 'B', 'C', 'D'], 2, false).expect("Incorrect alphabet");
+    let valid = e.get_config();
+    assert!(Enigma::from_config(&valid).is_ok());
+
+    let mut config = valid.clone();
+    config.alphabet = vec!['A', 'B', 'C', 'C'];
+    assert_eq!(
+        Enigma::from_config(&config).err(),
+        Some(EnigmaError::DuplicateSymbol(Component::Alphabet))
+    );
+
+    let mut config = valid.clone();
+    config.rotors[1].wiring = vec!['A', 'B', 'C'];
+    assert_eq!(
+        Enigma::from_config(&config).err(),
+        Some(EnigmaError::LengthMismatch {
+            component: Component::Rotor(1),
+            expected: 4,
+            actual: 3
+        })
+    );
+
+    let mut config = valid.clone();
+    config.rotors[0].wiring = vec!['A', 'B', 'C', 'E'];
+    assert_eq!(
+        Enigma::from_config(&config).err(),
+        Some(EnigmaError::InvalidPermutation(Component::Rotor(0)))
+    );
+
+    let mut config = valid.clone();
+    config.reflector = vec!['A', 'A', 'C', 'D'];
+    assert_eq!(
+        Enigma::from_config(&config).err(),
+        Some(EnigmaError::InvalidReflector)
+    );
+
+    let mut config = valid.clone();
+    config.commutator = Some(vec!['A', 'Z']);
+    assert!(matches!(
+        Enigma::from_config(&config),
+        Err(EnigmaError::InvalidPlugboard(_))
+    ));
+
+    let mut config: EnigmaConfig<char> = valid;
+    config.rotors[0].position = 'Z';
+    assert_eq!(
+        Enigma::from_config(&config).err(),
+        Some(EnigmaError::InvalidRotorSetting(0))
+    );
+}
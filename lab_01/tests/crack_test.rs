@@ -0,0 +1,158 @@
+use enigma::{
+    Enigma, EnigmaError, Mechanics, Plugboard,
+    analysis::{
+        crack::Cracker,
+        language::{Language, index_of_coincidence},
+    },
+    cfg::{EnigmaConfig, RotorConfig},
+    preset::Model,
+};
+
+const ENGLISH_MESSAGE: &str = "At dawn the convoy will leave the harbour and move north along the coast. \
+    The escort ships must keep radio silence until noon and report the position of every \
+    submarine they observe. Fuel and supplies for the second group are waiting in the old \
+    port and will be loaded tomorrow night if the weather allows it.";
+
+fn letters(language: Language, text: &str) -> Vec<char> {
+    let alphabet = language.alphabet();
+    text.chars()
+        .map(|c| language.normalize(c))
+        .filter(|c| alphabet.contains(c))
+        .collect()
+}
+
+// Воспроизводимая перестановка, чтобы тест не зависел от случайных роторов
+fn shuffled(alphabet: &[char], seed: u64) -> Vec<char> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut symbols = alphabet.to_vec();
+
+    for i in (1..symbols.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        symbols.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    symbols
+}
+
+fn matching(a: &[char], b: &[char]) -> f64 {
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / a.len() as f64
+}
+
+#[test]
+fn test_crack_english_preset() {
+    let plaintext = letters(Language::English, ENGLISH_MESSAGE);
+
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["IV", "I", "V"], "AAA", "RKQ", "")
+        .expect("Invalid preset");
+    e.set_plugboard(Some(
+        Plugboard::from_notation("AX QE LT").expect("Invalid plugboard"),
+    ))
+    .expect("Invalid plugboard");
+    let crypto = e
+        .encrypt(&plaintext)
+        .expect("Symbol in alphabet not founded");
+
+    let model = Language::English.model();
+    let cracker = Cracker::from_preset(Model::EnigmaI, "B", &model).expect("Invalid preset");
+    let names = cracker.rotor_names();
+    let order: Vec<usize> = ["IV", "I", "V"]
+        .iter()
+        .map(|r| names.iter().position(|n| n == r).expect("Unknown rotor"))
+        .collect();
+
+    let result = cracker
+        .crack_orders(&crypto, &[order])
+        .expect("Symbol in alphabet not founded");
+
+    assert_eq!(result.rotors, ["IV", "I", "V"]);
+    assert!(matching(&result.plaintext, &plaintext) > 0.9);
+}
+
+const RUSSIAN_MESSAGE: &str = "Конвой выйдет из порта на рассвете и пойдет на север вдоль берега. \
+    Корабли охранения должны соблюдать радиомолчание до полудня и сообщать о каждой \
+    замеченной подводной лодке. Топливо и продовольствие для второй группы ждут в старом \
+    порту и будут погружены завтра ночью, если позволит погода.";
+
+#[test]
+fn test_crack_russian_config() {
+    let plaintext = letters(Language::Russian, RUSSIAN_MESSAGE);
+
+    let alphabet = Language::Russian.alphabet();
+    let config = EnigmaConfig {
+        alphabet: alphabet.clone(),
+        commutator: Some(vec!['О', 'Ш', 'Е', 'Ф']),
+        entry: None,
+        reflector: shuffled(&alphabet, 1),
+        rotors: ['Ж', 'Ы', 'В']
+            .iter()
+            .zip(2..)
+            .map(|(&position, seed)| RotorConfig {
+                wiring: shuffled(&alphabet, seed),
+                notches: vec!['Я'],
+                ring: 'А',
+                position,
+                stationary: false,
+            })
+            .collect(),
+        mechanics: Mechanics::Historical,
+    };
+
+    let crypto = Enigma::from_config(&config)
+        .expect("Incorrect config")
+        .encrypt(&plaintext)
+        .expect("Symbol in alphabet not founded");
+
+    let model = Language::Russian.model();
+    let cracker = Cracker::from_config(&config, &model).expect("Incorrect config");
+    let result = cracker
+        .crack_orders(&crypto, &[vec![2, 1, 0]])
+        .expect("Symbol in alphabet not founded");
+
+    assert_eq!(result.config.rotors, config.rotors);
+    assert_eq!(result.plaintext, plaintext);
+
+    assert_eq!(
+        cracker.crack_orders(&crypto, &[]).err(),
+        Some(EnigmaError::InvalidSearch("no rotor orders to search"))
+    );
+
+    // Шесть роторов: 6! порядков по 33^6 позиций отклоняются до перебора
+    let mut large = config.clone();
+    large.rotors.extend(config.rotors.iter().cloned());
+    let cracker = Cracker::from_config(&large, &model).expect("Incorrect config");
+    let too_large = Some(EnigmaError::InvalidSearch("too many settings to search"));
+    assert_eq!(cracker.crack(&crypto).err(), too_large);
+    assert_eq!(
+        cracker
+            .crack_orders(&crypto, &[vec![0, 1, 2, 3, 4, 5]])
+            .err(),
+        too_large
+    );
+}
+
+#[test]
+fn test_index_of_coincidence() {
+    let model = Language::English.model();
+    assert!(Cracker::from_preset(Model::M4, "B-thin", &model).is_err());
+    assert!(Cracker::from_preset(Model::EnigmaI, "B", &Language::Russian.model()).is_err());
+
+    let count = |text: &[char]| {
+        let mut counts = vec![0; 26];
+        for c in text {
+            counts[(*c as u8 - b'A') as usize] += 1;
+        }
+        counts
+    };
+
+    let plaintext = letters(Language::English, ENGLISH_MESSAGE);
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+        .expect("Invalid preset");
+    let crypto = e
+        .encrypt(&plaintext)
+        .expect("Symbol in alphabet not founded");
+
+    assert!(index_of_coincidence(&count(&plaintext)) > 0.055);
+    assert!(index_of_coincidence(&count(&crypto)) < 0.045);
+}
@@ -0,0 +1,127 @@
+use std::fs::{self, File};
+
+use enigma::{
+    Component, Enigma, EnigmaError,
+    cfg::{BinConfigSerializer, ConfigSerializer, EnigmaConfig, TomlConfigSerializer},
+};
+
+mod common;
+
+// Входное колесо коммерческой Энигмы: клавиши подключены в порядке клавиатуры
+const QWERTZ: &str = "QWERTZUIOASDFGHJKPYXCVBNML";
+
+fn config() -> EnigmaConfig<char> {
+    let alphabet: Vec<char> = ('A'..='Z').collect();
+    let mut config = Enigma::from_seed(&alphabet, 5, false, 11)
+        .expect("Incorrect alphabet")
+        .get_config();
+
+    config.entry = Some(QWERTZ.chars().collect());
+    config.rotors[3].stationary = true;
+    config.rotors[4].stationary = true;
+    config
+}
+
+fn message() -> Vec<char> {
+    "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG"
+        .chars()
+        .cycle()
+        .take(30_000)
+        .collect()
+}
+
+#[test]
+fn test_entry_wheel() {
+    let msg = message();
+    let config = config();
+
+    let mut plain = config.clone();
+    plain.entry = None;
+    let without = Enigma::from_config(&plain)
+        .expect("Incorrect config")
+        .encrypt(&msg)
+        .expect("Symbol in alphabet not founded");
+
+    let mut identity = config.clone();
+    identity.entry = Some(('A'..='Z').collect());
+    let through_identity = Enigma::from_config(&identity)
+        .expect("Incorrect config")
+        .encrypt(&msg)
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(through_identity, without);
+
+    let mut e = Enigma::from_config(&config).expect("Incorrect config");
+    let crypto = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    assert_ne!(crypto, without);
+    assert!(crypto.iter().zip(&msg).all(|(c, m)| c != m));
+
+    e.reset();
+    assert_eq!(
+        e.decrypt(&crypto).expect("Symbol in alphabet not founded"),
+        msg
+    );
+
+    let mut invalid = config.clone();
+    invalid.entry = Some("QWERTZUIOASDFGHJKPYXCVBNMQ".chars().collect());
+    assert_eq!(
+        Enigma::from_config(&invalid).err(),
+        Some(EnigmaError::DuplicateSymbol(Component::EntryWheel))
+    );
+}
+
+#[test]
+fn test_stationary_rotors() {
+    let msg = message();
+    let mut e = Enigma::from_config(&config()).expect("Incorrect config");
+    let start = e.positions();
+
+    let crypto = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    let end = e.positions();
+    assert_eq!(end[3..], start[3..]);
+    assert_ne!(end[..3], start[..3]);
+
+    // Перемотка пропускает неподвижные роторы так же, как нажатия клавиш
+    e.seek(msg.len());
+    assert_eq!(e.positions(), end);
+    e.seek(12_345);
+    let tail = e
+        .encrypt(&msg[12_345..])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(tail, crypto[12_345..]);
+
+    // Позиции неподвижных роторов устанавливаются вручную
+    let mut positions = start.clone();
+    positions[4] = 'Q';
+    e.set_positions(&positions).expect("Invalid positions");
+    let shifted = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    assert_ne!(shifted, crypto);
+    assert_eq!(e.positions()[4], 'Q');
+}
+
+#[test]
+fn test_entry_and_stators_persisted() {
+    let config = config();
+    let path = common::temp_path("entry.conf");
+    let mut file = File::create(&path).expect("Can't create config file");
+    BinConfigSerializer::save_configs(&mut file, &config).expect("Can't save config");
+    let mut file = File::open(&path).expect("Can't open config file");
+    let loaded: EnigmaConfig<char> =
+        BinConfigSerializer::get_configs(&mut file).expect("Can't load config");
+    fs::remove_file(&path).ok();
+    assert_eq!(loaded, config);
+
+    let path = common::temp_path("entry.toml");
+    let mut file = File::create(&path).expect("Can't create config file");
+    TomlConfigSerializer::save_configs(&mut file, &config).expect("Can't save config");
+    let text = fs::read_to_string(&path).expect("Can't read config file");
+    let mut file = File::open(&path).expect("Can't open config file");
+    let loaded: EnigmaConfig<char> =
+        TomlConfigSerializer::get_configs(&mut file).expect("Can't load config");
+    fs::remove_file(&path).ok();
+    assert_eq!(loaded, config);
+    assert!(text.contains(&format!("entry = \"{}\"", QWERTZ)));
+    assert_eq!(text.matches("stationary = true").count(), 2);
+
+    let e = Enigma::from_config(&config).expect("Incorrect config");
+    assert_eq!(e.get_config(), config);
+}
@@ -0,0 +1,80 @@
+use enigma::{Enigma, EnigmaError, preset::Model};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[test]
+fn test_indicator_roundtrip() {
+    let mut e =
+        Enigma::from_seed(&(0..=255).collect::<Vec<u8>>(), 3, true, 5).expect("Incorrect alphabet");
+    let msg = b"The same greeting in every message".to_vec();
+
+    let mut rng = ChaCha20Rng::seed_from_u64(5);
+    let first_key = e.random_message_key_with_rng(&mut rng);
+    let second_key = e.random_message_key_with_rng(&mut rng);
+    assert_eq!(first_key, [160, 156, 126]);
+    assert_eq!(second_key, [255, 72, 208]);
+
+    let first = e
+        .encrypt_with_indicator(&msg, &first_key)
+        .expect("Symbol in alphabet not founded");
+    let second = e
+        .encrypt_with_indicator(&msg, &second_key)
+        .expect("Symbol in alphabet not founded");
+
+    assert_eq!(first.len(), msg.len() + 3);
+    assert_ne!(first[3..], second[3..]);
+
+    for crypto in [first, second] {
+        let decrypt = e
+            .decrypt_with_indicator(&crypto)
+            .expect("Symbol in alphabet not founded");
+        assert_eq!(decrypt, msg);
+    }
+}
+
+#[test]
+fn test_indicator_uses_daily_key() {
+    let mut e: Enigma<char> =
+        Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Invalid preset");
+
+    // Ключ сообщения шифруется с суточных позиций AAA
+    let indicator = e
+        .encrypt_indicator(&['A', 'A', 'A'])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(indicator, ['B', 'D', 'Z']);
+    assert_eq!(e.positions(), ['A', 'A', 'A']);
+
+    e.set_positions(&['Q', 'X', 'B'])
+        .expect("Invalid positions");
+    assert_eq!(e.positions(), ['Q', 'X', 'B']);
+    e.reset();
+    assert_eq!(e.positions(), ['A', 'A', 'A']);
+}
+
+#[test]
+fn test_indicator_errors() {
+    let mut e: Enigma<char> =
+        Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Invalid preset");
+
+    assert_eq!(
+        e.decrypt_with_indicator(&['A', 'B']),
+        Err(EnigmaError::MissingIndicator)
+    );
+    assert_eq!(
+        e.set_positions(&['A', 'B']),
+        Err(EnigmaError::PositionsCount {
+            expected: 3,
+            actual: 2
+        })
+    );
+    assert_eq!(
+        e.set_positions(&['A', 'b', 'C']),
+        Err(EnigmaError::InvalidRotorSetting(1))
+    );
+    assert_eq!(
+        e.encrypt_with_indicator(&['A', 'b'], &['A', 'A', 'A']),
+        Err(EnigmaError::SymbolNotInAlphabet(4))
+    );
+}
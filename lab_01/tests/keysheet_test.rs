@@ -0,0 +1,101 @@
+use std::fs::{self, File};
+
+use enigma::{
+    Enigma, Plugboard,
+    keysheet::{Date, KeySheet},
+    preset::Model,
+};
+
+mod common;
+
+fn date(s: &str) -> Date {
+    s.parse().expect("Invalid date")
+}
+
+#[test]
+fn test_keysheet_generation() {
+    let sheet = KeySheet::generate(Model::M4, date("2024-02-27"), 5, 42);
+
+    assert_eq!(
+        sheet,
+        KeySheet::generate(Model::M4, date("2024-02-27"), 5, 42)
+    );
+    assert_ne!(
+        sheet,
+        KeySheet::generate(Model::M4, date("2024-02-27"), 5, 43)
+    );
+
+    // Зерно из распечатки должно воспроизводить таблицу и после обновления rand
+    let first = &sheet.keys[0];
+    assert_eq!(first.reflector, "B-thin");
+    assert_eq!(first.rotors, ["Gamma", "I", "VII", "III"]);
+    assert_eq!(first.rings, "UERE");
+    assert_eq!(first.positions, "ZIEV");
+    assert_eq!(first.plugboard, "ZY XH QO FU IE AK DJ RB GP LV");
+
+    let dates: Vec<String> = sheet.keys.iter().map(|k| k.date.to_string()).collect();
+    assert_eq!(
+        dates,
+        [
+            "2024-02-27",
+            "2024-02-28",
+            "2024-02-29",
+            "2024-03-01",
+            "2024-03-02"
+        ]
+    );
+
+    for key in &sheet.keys {
+        assert!(["Beta", "Gamma"].contains(&key.rotors[0].as_str()));
+        assert_eq!(key.plugboard.split_whitespace().count(), 10);
+
+        let mut e: Enigma<u8> = key.enigma(Model::M4).expect("Invalid daily key");
+        let mut expected: Enigma<u8> = Enigma::from_preset(
+            Model::M4,
+            &key.reflector,
+            &key.rotors.iter().map(String::as_str).collect::<Vec<_>>(),
+            &key.rings,
+            &key.positions,
+            "",
+        )
+        .expect("Invalid preset");
+        expected
+            .set_plugboard(Some(
+                Plugboard::from_notation(&key.plugboard).expect("Invalid plugboard"),
+            ))
+            .expect("Invalid plugboard");
+
+        let msg = b"WETTERBERICHT";
+        assert_eq!(e.encrypt(msg), expected.encrypt(msg));
+    }
+}
+
+#[test]
+fn test_keysheet_save_load() {
+    let sheet = KeySheet::generate(Model::EnigmaI, date("2026-10-01"), 31, 7);
+    let path = common::temp_path("keysheet.toml");
+
+    let mut file = File::create(&path).expect("Can't create key sheet file");
+    sheet.save(&mut file).expect("Can't save key sheet");
+    let mut file = File::open(&path).expect("Can't open key sheet file");
+    let loaded = KeySheet::load(&mut file).expect("Can't load key sheet");
+    fs::remove_file(&path).ok();
+
+    assert_eq!(loaded, sheet);
+    assert!(loaded.enigma::<u8>(date("2026-10-31")).is_ok());
+    assert!(loaded.enigma::<u8>(date("2026-11-01")).is_err());
+
+    let printable = sheet.to_printable();
+    assert!(printable.find("2026-10-31") < printable.find("2026-10-01"));
+}
+
+#[test]
+fn test_date_parsing() {
+    assert_eq!(date("1999-12-31").add_days(1), date("2000-01-01"));
+    assert_eq!(date("2100-02-28").add_days(1), date("2100-03-01"));
+    assert_eq!(date("1970-01-01").add_days(-1), date("1969-12-31"));
+
+    for invalid in ["2023-02-29", "2024-13-01", "2024-1", "today", "2024-01-00"] {
+        assert!(invalid.parse::<Date>().is_err(), "{}", invalid);
+    }
+}
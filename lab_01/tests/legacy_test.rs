@@ -0,0 +1,129 @@
+use std::fs::{self, File};
+
+use enigma::{
+    Enigma, EnigmaError, Mechanics,
+    cfg::{BinConfigSerializer, ConfigSerializer, EnigmaConfig, RotorConfig, TomlConfigSerializer},
+    stepping::EnigmaStepping,
+};
+
+mod common;
+
+// Машина и шифротекст получены версией 0.1: `Enigma::from_config` с теми же
+// коммутатором, рефлектором и роторами
+const CIPHERTEXT: &[u8] = b"DDEACBBAEFCDDAAAFDCFDAFEFDBBDCFEBBDBDDEACBBAEFCDDAAAFDCFDAFE";
+
+fn legacy_config() -> EnigmaConfig<u8> {
+    EnigmaConfig {
+        alphabet: b"ABCDEF".to_vec(),
+        commutator: Some(b"CAFBED".to_vec()),
+        entry: None,
+        reflector: b"BDAFCE".to_vec(),
+        rotors: [b"DAEBFC", b"FCADBE", b"BEFACD"]
+            .iter()
+            .map(|wiring| RotorConfig {
+                wiring: wiring.to_vec(),
+                notches: vec![b'A'],
+                ring: b'A',
+                position: b'A',
+                stationary: false,
+            })
+            .collect(),
+        mechanics: Mechanics::Legacy,
+    }
+}
+
+fn message() -> Vec<u8> {
+    b"ABCDEF"
+        .iter()
+        .cycle()
+        .take(CIPHERTEXT.len())
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_legacy_mechanics_match_v0_1() {
+    let mut e = Enigma::from_config(&legacy_config()).expect("Incorrect config");
+    let crypto = e
+        .encrypt(&message())
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(crypto, CIPHERTEXT);
+
+    e.reset();
+    let decrypt = e
+        .decrypt(CIPHERTEXT)
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(decrypt, message());
+
+    e.reset();
+    let (traced, _) = e
+        .encrypt_traced(&message())
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(traced, CIPHERTEXT);
+
+    // Та же проводка с исторической механикой шифрует иначе
+    let mut historical = legacy_config();
+    historical.mechanics = Mechanics::Historical;
+    let mut e = Enigma::from_config(&historical).expect("Incorrect config");
+    assert_ne!(
+        e.encrypt(&message())
+            .expect("Symbol in alphabet not founded"),
+        CIPHERTEXT
+    );
+}
+
+#[test]
+fn test_legacy_config_roundtrip() {
+    let config = legacy_config();
+
+    let path = common::temp_path("legacy.conf");
+    let mut file = File::create(&path).expect("Can't create config file");
+    BinConfigSerializer::save_configs(&mut file, &config).expect("Can't save config");
+    let mut file = File::open(&path).expect("Can't open config file");
+    let loaded: EnigmaConfig<u8> =
+        BinConfigSerializer::get_configs(&mut file).expect("Can't load config");
+    fs::remove_file(&path).ok();
+    assert_eq!(loaded, config);
+
+    let path = common::temp_path("legacy.toml");
+    let mut file = File::create(&path).expect("Can't create config file");
+    TomlConfigSerializer::save_configs(&mut file, &config).expect("Can't save config");
+    let text = fs::read_to_string(&path).expect("Can't read config file");
+    let mut file = File::open(&path).expect("Can't open config file");
+    let loaded: EnigmaConfig<u8> =
+        TomlConfigSerializer::get_configs(&mut file).expect("Can't load config");
+    fs::remove_file(&path).ok();
+    assert_eq!(loaded, config);
+    assert!(text.contains("legacy = true"));
+}
+
+#[test]
+fn test_legacy_state_and_seek() {
+    let mut e = Enigma::from_config(&legacy_config()).expect("Incorrect config");
+    let msg = message();
+
+    e.encrypt(&msg[..17])
+        .expect("Symbol in alphabet not founded");
+    let state = e.get_state().expect("Stepping not saved in config");
+    let mut resumed = Enigma::from_state(&state).expect("Incorrect state");
+    assert_eq!(
+        resumed
+            .encrypt(&msg[17..])
+            .expect("Symbol in alphabet not founded"),
+        CIPHERTEXT[17..]
+    );
+
+    for offset in [0, 5, 6, 36, 43] {
+        e.seek(offset);
+        assert_eq!(
+            e.encrypt(&msg[offset..])
+                .expect("Symbol in alphabet not founded"),
+            CIPHERTEXT[offset..],
+            "offset {}",
+            offset
+        );
+    }
+
+    e.set_stepping(Box::new(EnigmaStepping));
+    assert_eq!(e.get_state().err(), Some(EnigmaError::UnsavedStepping));
+}
@@ -0,0 +1,62 @@
+use enigma::{Enigma, EnigmaError, preset::Model};
+
+mod common;
+
+#[test]
+fn test_parallel_matches_encrypt() {
+    let mut e = common::byte_enigma(4, true);
+    let data = common::byte_message();
+    let expected = e.encrypt(&data).expect("Symbol in alphabet not founded");
+
+    for threads in [0, 1, 2, 3, 8, 300_000] {
+        e.reset();
+        let crypto = e
+            .encrypt_parallel(&data, threads)
+            .expect("Symbol in alphabet not founded");
+        assert_eq!(crypto, expected, "threads {}", threads);
+    }
+
+    // После шифрования роторы стоят там же, где после `encrypt`
+    e.reset();
+    e.encrypt_parallel(&data[..1000], 4)
+        .expect("Symbol in alphabet not founded");
+    let tail = e
+        .encrypt(&data[1000..])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(tail, expected[1000..]);
+}
+
+#[test]
+fn test_parallel_preset_roundtrip() {
+    let mut e: Enigma<char> =
+        Enigma::from_preset(Model::M3, "B", &["VI", "VII", "VIII"], "AAA", "ADU", "")
+            .expect("Invalid preset");
+    let msg: Vec<char> = "ATTACKATDAWN".chars().cycle().take(20_000).collect();
+
+    let expected = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    e.reset();
+    let crypto = e
+        .encrypt_parallel(&msg, 7)
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(crypto, expected);
+
+    e.reset();
+    let decrypt = e
+        .decrypt_parallel(&crypto, 5)
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(decrypt, msg);
+}
+
+#[test]
+fn test_parallel_error_position() {
+    let mut e: Enigma<u8> =
+        Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Invalid preset");
+    let mut msg = vec![b'A'; 10_000];
+    msg[7_777] = b'a';
+
+    assert_eq!(
+        e.encrypt_parallel(&msg, 4),
+        Err(EnigmaError::SymbolNotInAlphabet(7_777))
+    );
+}
@@ -0,0 +1,80 @@
+use enigma::{Enigma, Plugboard, preset::Model};
+
+#[test]
+fn test_barbarossa_message() {
+    // Сообщение 1941 года: Enigma I, роторы II IV V, кольца 02 21 12, позиция BLA
+    let crypto_str = "EDPUDNRGYSZRCXNUYTPOMRMBOFKTBZREZKMLXLVEFGUEYSIOZVEQMIKUBPMMYLKLTTDEISMDICAGYKUACTCDOMOHWXMUUIAUBSTSLRNBZSZWNRFXWFYSSXJZVIJHIDISHPRKLKAYUPADTXQSPINQMATLPIFSVKDASCTACDPBOPVHJK";
+    let plain_str = "AUFKLXABTEILUNGXVONXKURTINOWAXKURTINOWAXNORDWESTLXSEBEZXSEBEZXUAFFLIEGERSTRASZERIQTUNGXDUBROWKIXDUBROWKIXOPOTSCHKAXOPOTSCHKAXUMXEINSAQTDREINULLXUHRANGETRETENXANGRIFFXINFXRGTX";
+
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["II", "IV", "V"], "BUL", "BLA", "")
+        .expect("Incorrect preset");
+    e.set_plugboard(Some(
+        Plugboard::from_notation("AV BS CG DL FU HZ IN KM OW RX").expect("Incorrect plugboard"),
+    ))
+    .expect("Incorrect plugboard");
+
+    let crypto: Vec<char> = crypto_str.chars().collect();
+    let decrypt: String = e
+        .decrypt(&crypto)
+        .expect("Symbol in alphabet not founded")
+        .iter()
+        .collect();
+
+    assert_eq!(decrypt, plain_str);
+}
+
+#[test]
+fn test_unsteckered_symbols_pass_through() {
+    let plugboard = Plugboard::<char>::from_notation("AB CD").expect("Incorrect plugboard");
+
+    assert_eq!(plugboard.swap(&'A'), 'B');
+    assert_eq!(plugboard.swap(&'D'), 'C');
+    assert_eq!(plugboard.swap(&'Z'), 'Z');
+    assert_eq!(plugboard.get_config(), vec!['A', 'B', 'C', 'D']);
+}
+
+#[test]
+fn test_plugboard_validation() {
+    assert!(Plugboard::<char>::from_notation("").is_ok());
+    assert!(Plugboard::<char>::from_notation("AA").is_err());
+    assert!(Plugboard::<char>::from_notation("AB BC").is_err());
+    assert!(Plugboard::<char>::from_notation("AB C").is_err());
+    assert!(Plugboard::<u8>::from_notation("AБ").is_err());
+    assert!(Plugboard::from_config(&[1u8, 2, 3]).is_err());
+}
+
+#[test]
+fn test_live_plugging() {
+    let mut e =
+        Enigma::<char>::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Incorrect preset");
+    let plain: Vec<char> = "AAAAA".chars().collect();
+
+    e.plug(&'A', &'B').expect("Incorrect plugboard");
+    e.plug(&'C', &'D').expect("Incorrect plugboard");
+    // Новый кабель вынимает прежние кабели своих символов
+    e.plug(&'B', &'C').expect("Incorrect plugboard");
+    assert_eq!(e.get_config().commutator, Some(vec!['B', 'C']));
+    assert!(e.plug(&'E', &'E').is_err());
+    assert!(e.plug(&'E', &'Ж').is_err());
+    assert_eq!(e.get_config().commutator, Some(vec!['B', 'C']));
+
+    let mut expected =
+        Enigma::<char>::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Incorrect preset");
+    expected
+        .set_plugboard(Some(
+            Plugboard::from_notation("BC").expect("Incorrect plugboard"),
+        ))
+        .expect("Incorrect plugboard");
+    assert_eq!(e.encrypt(&plain), expected.encrypt(&plain));
+
+    e.unplug(&'Z').expect("Incorrect plugboard");
+    e.unplug(&'C').expect("Incorrect plugboard");
+    assert_eq!(e.get_config().commutator, None);
+    e.reset();
+    assert_eq!(
+        e.encrypt(&plain).expect("Symbol in alphabet not founded"),
+        "BDZGO".chars().collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,161 @@
+use enigma::{Enigma, preset::Model};
+
+fn encrypt_str(e: &mut Enigma<char>, msg: &str) -> String {
+    let input: Vec<char> = msg.chars().collect();
+    e.encrypt(&input)
+        .expect("Symbol in alphabet not founded")
+        .iter()
+        .collect()
+}
+
+#[test]
+fn test_enigma_i_preset() {
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+        .expect("Incorrect preset");
+
+    assert_eq!(encrypt_str(&mut e, "AAAAA"), "BDZGO");
+
+    e.reset();
+    assert_eq!(encrypt_str(&mut e, "BDZGO"), "AAAAA");
+}
+
+#[test]
+fn test_m4_preset_compatible_with_enigma_i() {
+    // Бета-ротор в положении A с тонким рефлектором B эквивалентен рефлектору B
+    let mut e = Enigma::from_preset(
+        Model::M4,
+        "B-thin",
+        &["Beta", "I", "II", "III"],
+        "AAAA",
+        "AAAA",
+        "",
+    )
+    .expect("Incorrect preset");
+
+    assert_eq!(encrypt_str(&mut e, "AAAAA"), "BDZGO");
+}
+
+#[test]
+fn test_m4_greek_rotor_positions() {
+    let mut e = Enigma::from_preset(
+        Model::M4,
+        "B-thin",
+        &["Beta", "II", "IV", "I"],
+        "AAAV",
+        "VJNA",
+        "",
+    )
+    .expect("Incorrect preset");
+
+    // Греческий ротор - самый левый и не вращается
+    assert_eq!(e.positions(), vec!['A', 'N', 'J', 'V']);
+    encrypt_str(&mut e, &"A".repeat(1000));
+    assert_eq!(e.positions()[3], 'V');
+
+    e.set_positions(&['C', 'B', 'A', 'Q'])
+        .expect("Invalid positions");
+    assert_eq!(e.positions(), vec!['C', 'B', 'A', 'Q']);
+    assert_eq!(e.get_config().rotors.len(), 4);
+    assert!(e.get_config().rotors[3].stationary);
+}
+
+#[test]
+fn test_preset_validation() {
+    assert!(
+        Enigma::<char>::from_preset(Model::EnigmaI, "B", &["I", "II", "VI"], "AAA", "AAA", "")
+            .is_err()
+    );
+    assert!(
+        Enigma::<char>::from_preset(Model::M3, "B-thin", &["I", "II", "III"], "AAA", "AAA", "")
+            .is_err()
+    );
+    assert!(
+        Enigma::<char>::from_preset(
+            Model::M4,
+            "B-thin",
+            &["I", "II", "III", "IV"],
+            "AAAA",
+            "AAAA",
+            ""
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_double_stepping() {
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+        .expect("Incorrect preset");
+
+    // Позиции роторов возвращаются справа налево: быстрый ротор первый
+    encrypt_str(&mut e, &"A".repeat(100));
+    assert_eq!(e.positions(), vec!['W', 'E', 'A']);
+
+    encrypt_str(&mut e, "A");
+    assert_eq!(e.positions(), vec!['X', 'F', 'B']);
+
+    encrypt_str(&mut e, "A");
+    assert_eq!(e.positions(), vec!['Y', 'F', 'B']);
+}
+
+#[test]
+fn test_decrypt_across_turnover() {
+    let msg = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG".repeat(20);
+
+    let mut e = Enigma::from_preset(Model::M3, "C", &["VI", "VIII", "V"], "AAA", "AAA", "")
+        .expect("Incorrect preset");
+    let crypto = encrypt_str(&mut e, &msg);
+
+    e.reset();
+    assert_eq!(encrypt_str(&mut e, &crypto), msg);
+}
+
+#[test]
+fn test_ring_settings() {
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "BBB", "AAA", "")
+        .expect("Incorrect preset");
+
+    assert_eq!(encrypt_str(&mut e, "AAAAA"), "EWTYX");
+}
+
+#[test]
+fn test_start_positions_restored_by_reset() {
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "ADU", "")
+        .expect("Incorrect preset");
+
+    encrypt_str(&mut e, "AAA");
+    assert_eq!(e.positions(), vec!['X', 'F', 'B']);
+
+    e.reset();
+    assert_eq!(e.positions(), vec!['U', 'D', 'A']);
+}
+
+#[test]
+fn test_m4_u534_message() {
+    // Сообщение U-534 от 1 мая 1945 года: M4, коммутатор, бета-ротор
+    let mut e = Enigma::from_preset(
+        Model::M4,
+        "B-thin",
+        &["Beta", "II", "IV", "I"],
+        "AAAV",
+        "VJNA",
+        "AT BL DF GJ HM NW OP QY RZ VX",
+    )
+    .expect("Incorrect preset");
+
+    let crypto = "NCZWVUSXPNYMINHZXMQXSFWXWLKJAHSHNMCOCCAKUQPMKCSMHKSEINJUSBLKIOSXCKUBHMLLXCSJUSRRDVKOHULXWCCBGVLIYXEOAHXRHKKFVDREWEZLXOBAFGYUJQUKGRTVUKAMEURBVEKSUHHVOYHABCJWMAKLFKLMYFVNRIZRVVRTKOFDANJMOLBGFFLEOPRGTFLVRHOWOPBEKVWMUQFMPWPARMFHAGKXIIBG";
+    let plain = "VONVONJLOOKSJHFFTTTEINSEINSDREIZWOYYQNNSNEUNINHALTXXBEIANGRIFFUNTERWASSERGEDRUECKTYWABOSXLETZTERGEGNERSTANDNULACHTDREINULUHRMARQUANTONJOTANEUNACHTSEYHSDREIYZWOZWONULGRADYACHTSMYSTOSSENACHXEKNSVIERMBFAELLTYNNNNNNOOOVIERYSICHTEINSNULL";
+    assert_eq!(encrypt_str(&mut e, crypto), plain);
+
+    assert!(
+        Enigma::<char>::from_preset(
+            Model::M4,
+            "B-thin",
+            &["Beta", "II", "IV", "I"],
+            "AAAV",
+            "VJNA",
+            "AT BLX"
+        )
+        .is_err()
+    );
+}
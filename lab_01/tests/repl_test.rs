@@ -0,0 +1,72 @@
+use std::io::Cursor;
+
+use enigma::{Enigma, preset::Model, repl};
+
+fn enigma_i() -> Enigma<char> {
+    Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+        .expect("Invalid preset")
+}
+
+fn session(enigma: &mut Enigma<char>, script: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    repl::run(enigma, Cursor::new(script), &mut out).expect("Session failed");
+
+    String::from_utf8(out)
+        .expect("Output is not UTF-8")
+        .lines()
+        .map(|line| line.trim_start_matches("> ").to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+#[test]
+fn test_key_presses_light_lamps() {
+    let mut e = enigma_i();
+    let out = session(&mut e, "aaaaa\n:reset\nBDZGO\n");
+
+    assert!(out.contains(&" Q  W  E  R  T  Z  U  I [O]".to_string()));
+    assert!(out.contains(&"Лампы: BDZGO".to_string()));
+    assert!(out.contains(&"Лампы: AAAAA".to_string()));
+    assert_eq!(
+        out.last().map(String::as_str),
+        Some("Роторы: [A] [A] [F]  Коммутатор: ")
+    );
+    assert_eq!(e.positions(), vec!['F', 'A', 'A']);
+}
+
+#[test]
+fn test_rotor_commands() {
+    let mut e = enigma_i();
+    let out = session(
+        &mut e,
+        ":set adu\n:step 2\n:step x\n:set AB\n:foo\n:q\n:step\n",
+    );
+
+    assert!(out.contains(&"Роторы: [A] [D] [U]  Коммутатор: ".to_string()));
+    assert!(out.contains(&"Роторы: [A] [E] [W]  Коммутатор: ".to_string()));
+    assert!(out.contains(&"Неверное число шагов 'x'".to_string()));
+    assert!(out.contains(&"Got 2 rotor positions, expected 3".to_string()));
+    assert!(out.contains(&"Неизвестная команда ':foo', справка - :help".to_string()));
+
+    // Команды после :q не выполняются
+    assert_eq!(e.positions(), vec!['W', 'E', 'A']);
+}
+
+#[test]
+fn test_plugboard_commands() {
+    let mut e = enigma_i();
+    let out = session(&mut e, ":plug AB cd\n:plug ABC\n:unplug a\n:reset\nHELLO\n");
+
+    assert!(out.contains(&"Роторы: [A] [A] [A]  Коммутатор: AB CD".to_string()));
+    assert!(out.contains(&"Кабель соединяет два символа, а не 'ABC'".to_string()));
+    assert!(out.contains(&"Роторы: [A] [A] [A]  Коммутатор: CD".to_string()));
+
+    let mut expected = enigma_i();
+    expected.plug(&'C', &'D').expect("Invalid plug");
+    let lamps: String = expected
+        .encrypt(&['H', 'E', 'L', 'L', 'O'])
+        .expect("Symbol in alphabet not founded")
+        .iter()
+        .collect();
+    assert!(out.contains(&format!("Лампы: {}", lamps)));
+}
@@ -0,0 +1,63 @@
+use enigma::{Enigma, Plugboard};
+use rand::{SeedableRng, rngs::StdRng};
+
+fn byte_alphabet() -> Vec<u8> {
+    (0..=255).collect()
+}
+
+#[test]
+fn test_injected_rng() {
+    let machine = |seed: u64| {
+        Enigma::from_alphabet_with_rng(&byte_alphabet(), 4, true, &mut StdRng::seed_from_u64(seed))
+            .expect("Incorrect alphabet")
+            .get_config()
+    };
+
+    assert_eq!(machine(7), machine(7));
+    assert_ne!(machine(7), machine(8));
+
+    let plugboard = |seed: u64| {
+        Plugboard::from_alphabet_with_rng(&byte_alphabet(), &mut StdRng::seed_from_u64(seed))
+            .get_config()
+    };
+    assert_eq!(plugboard(7), plugboard(7));
+}
+
+#[test]
+fn test_seed() {
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 13 % 256) as u8).collect();
+
+    let mut sender =
+        Enigma::from_seed(&byte_alphabet(), 3, true, 2024).expect("Incorrect alphabet");
+    let crypto = sender
+        .encrypt(&data)
+        .expect("Symbol in alphabet not founded");
+
+    // Порядок символов в алфавите на машину не влияет
+    let mut reversed = byte_alphabet();
+    reversed.reverse();
+    let mut receiver = Enigma::from_seed(&reversed, 3, true, 2024).expect("Incorrect alphabet");
+    assert_eq!(
+        receiver
+            .decrypt(&crypto)
+            .expect("Symbol in alphabet not founded"),
+        data
+    );
+
+    let other = Enigma::from_seed(&byte_alphabet(), 3, true, 2025).expect("Incorrect alphabet");
+    assert_ne!(other.get_config(), sender.get_config());
+}
+
+#[test]
+fn test_passphrase() {
+    let alphabet: Vec<char> = ('A'..='Z').collect();
+    let machine = |passphrase: &str| {
+        Enigma::from_passphrase(&alphabet, 3, true, passphrase)
+            .expect("Incorrect alphabet")
+            .get_config()
+    };
+
+    assert_eq!(machine("Wotan"), machine("Wotan"));
+    assert_ne!(machine("Wotan"), machine("wotan"));
+    assert_ne!(machine(""), machine(" "));
+}
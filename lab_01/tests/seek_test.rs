@@ -0,0 +1,121 @@
+use enigma::{Enigma, Symbol, preset::Model};
+
+// Состояние после `seek` должно совпадать с состоянием после
+// посимвольного шифрования того же количества символов
+fn assert_seek_matches<T: Symbol + std::fmt::Debug>(e: &mut Enigma<T>, msg: &[T]) {
+    e.reset();
+    let expected = e.encrypt(msg).expect("Symbol in alphabet not founded");
+
+    e.reset();
+    let mut positions = Vec::with_capacity(msg.len());
+    for symb in msg {
+        positions.push(e.positions());
+        e.encrypt(std::slice::from_ref(symb))
+            .expect("Symbol in alphabet not founded");
+    }
+
+    for offset in (0..msg.len()).step_by(msg.len() / 500 + 1) {
+        e.seek(offset);
+        assert_eq!(e.positions(), positions[offset], "offset {}", offset);
+
+        let end = msg.len().min(offset + 100);
+        let window = e
+            .encrypt(&msg[offset..end])
+            .expect("Symbol in alphabet not founded");
+        assert_eq!(window, expected[offset..end], "offset {}", offset);
+    }
+}
+
+#[test]
+fn test_seek_preset_double_stepping() {
+    // Рядом с вырезами среднего ротора, включая роторы с двумя вырезами
+    for rotors in [["I", "II", "III"], ["VI", "VII", "VIII"]] {
+        let mut e: Enigma<u8> =
+            Enigma::from_preset(Model::M3, "B", &rotors, "AAA", "ADU", "").expect("Invalid preset");
+        let msg = vec![b'A'; 26 * 26 * 27];
+
+        assert_seek_matches(&mut e, &msg);
+    }
+}
+
+#[test]
+fn test_seek_random_machine() {
+    let alphabet: Vec<u8> = (0..=255).collect();
+    let msg: Vec<u8> = (0..100_000u32).map(|i| (i * 13 % 256) as u8).collect();
+
+    for rotors_cnt in [0, 1, 2, 5] {
+        let mut e = Enigma::from_alphabet(&alphabet, rotors_cnt, true).expect("Incorrect alphabet");
+        assert_seek_matches(&mut e, &msg);
+    }
+}
+
+#[test]
+fn test_seek_decrypts_slice() {
+    let mut e: Enigma<char> =
+        Enigma::from_preset(Model::EnigmaI, "B", &["II", "IV", "V"], "BUL", "BLA", "")
+            .expect("Invalid preset");
+    let msg: Vec<char> = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG"
+        .chars()
+        .cycle()
+        .take(5000)
+        .collect();
+
+    e.reset();
+    let crypto = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+
+    e.seek(3000);
+    let slice = e
+        .decrypt(&crypto[3000..3100])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(slice, msg[3000..3100]);
+}
+
+#[test]
+fn test_advance_matches_key_presses() {
+    let mut e = Enigma::<u8>::from_preset(Model::M3, "B", &["VI", "VII", "VIII"], "AAA", "ZYX", "")
+        .expect("Incorrect preset");
+    let msg = vec![b'A'; 2000];
+
+    e.encrypt(&msg[..1234])
+        .expect("Symbol in alphabet not founded");
+    let tail = e
+        .encrypt(&msg[1234..])
+        .expect("Symbol in alphabet not founded");
+
+    e.reset();
+    e.advance(1000);
+    e.advance(234);
+    assert_eq!(
+        e.encrypt(&msg[1234..])
+            .expect("Symbol in alphabet not founded"),
+        tail
+    );
+}
+
+#[test]
+fn test_seek_large_offset() {
+    // Позиции после 10^9 нажатий получены поштучным моделированием
+    let mut e: Enigma<char> =
+        Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Invalid preset");
+    e.seek(1_000_000_000);
+    assert_eq!(e.positions(), vec!['M', 'O', 'Q']);
+
+    let msg: Vec<char> = ('A'..='Z').cycle().take(777).collect();
+    let expected = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    let end = e.positions();
+    e.seek(1_000_000_777);
+    assert_eq!(e.positions(), end);
+
+    e.seek(1_000_000_000);
+    assert_eq!(
+        e.encrypt(&msg).expect("Symbol in alphabet not founded"),
+        expected
+    );
+
+    // Короткий период маленького алфавита повторяется много раз
+    let alphabet: Vec<u8> = (0..6).collect();
+    let mut e = Enigma::from_seed(&alphabet, 4, false, 3).expect("Incorrect alphabet");
+    let msg: Vec<u8> = (0..20_000u32).map(|i| (i % 6) as u8).collect();
+    assert_seek_matches(&mut e, &msg);
+}
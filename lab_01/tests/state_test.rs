@@ -0,0 +1,127 @@
+use std::fs::{self, File};
+
+use enigma::{
+    Enigma, EnigmaError,
+    cfg::{BinConfigSerializer, ConfigSerializer, ConfigSymbol, EnigmaState, TomlConfigSerializer},
+    preset::Model,
+    stepping::{CogWheel, Typex},
+};
+
+mod common;
+
+fn preset() -> Enigma<char> {
+    Enigma::from_preset(
+        Model::M4,
+        "B-thin",
+        &["Beta", "II", "IV", "I"],
+        "AAAV",
+        "VJNA",
+        "",
+    )
+    .expect("Invalid preset")
+}
+
+fn message() -> Vec<char> {
+    ('A'..='Z').cycle().take(500).collect()
+}
+
+#[test]
+fn test_resume_from_state() {
+    let message = message();
+    let expected = preset()
+        .encrypt(&message)
+        .expect("Symbol in alphabet not founded");
+
+    let mut e = preset();
+    let head = e
+        .encrypt(&message[..321])
+        .expect("Symbol in alphabet not founded");
+    let state = e.get_state().expect("Stepping not saved in config");
+    assert_eq!(state.positions, e.positions());
+
+    let mut resumed = Enigma::from_state(&state).expect("Invalid state");
+    let tail = resumed
+        .encrypt(&message[321..])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!([head, tail].concat(), expected);
+
+    // Начальные позиции остаются в конфигурации
+    resumed.reset();
+    assert_eq!(
+        resumed
+            .encrypt(&message)
+            .expect("Symbol in alphabet not founded"),
+        expected
+    );
+}
+
+fn roundtrip<S: ConfigSerializer<T>, T: ConfigSymbol + Clone + PartialEq + std::fmt::Debug>(
+    state: &EnigmaState<T>,
+    filename: &str,
+) {
+    let path = common::temp_path(filename);
+
+    let mut file = File::create(&path).expect("Can't create state file");
+    S::save_state(&mut file, state).expect("Can't save state");
+
+    let mut file = File::open(&path).expect("Can't open state file");
+    let loaded = S::get_state(&mut file).expect("Can't load state");
+    fs::remove_file(&path).ok();
+
+    assert_eq!(&loaded, state);
+}
+
+#[test]
+fn test_state_serializers() {
+    let mut e = preset();
+    e.encrypt(&message())
+        .expect("Symbol in alphabet not founded");
+    let state = e.get_state().expect("Stepping not saved in config");
+    roundtrip::<BinConfigSerializer, _>(&state, "state.bin");
+    roundtrip::<TomlConfigSerializer, _>(&state, "state.toml");
+
+    let mut e = Enigma::from_alphabet(&(0..=255).collect::<Vec<u8>>(), 5, true)
+        .expect("Incorrect alphabet");
+    e.encrypt(&[7; 1000])
+        .expect("Symbol in alphabet not founded");
+    let state = e.get_state().expect("Stepping not saved in config");
+    roundtrip::<BinConfigSerializer, _>(&state, "state_bytes.bin");
+    roundtrip::<TomlConfigSerializer, _>(&state, "state_bytes.toml");
+}
+
+#[test]
+fn test_invalid_state() {
+    let mut state = preset().get_state().expect("Stepping not saved in config");
+    state.positions.pop();
+    assert_eq!(
+        Enigma::from_state(&state).err(),
+        Some(EnigmaError::PositionsCount {
+            expected: 4,
+            actual: 3
+        })
+    );
+
+    state.positions.push('ж');
+    assert_eq!(
+        Enigma::from_state(&state).err(),
+        Some(EnigmaError::InvalidRotorSetting(3))
+    );
+}
+
+#[test]
+fn test_state_refused_for_unsaved_stepping() {
+    let message = message();
+
+    // Колесо посреди оборота: из снимка машина продолжила бы с нулевого зуба
+    // и механикой Энигмы
+    let mut e = preset();
+    e.set_stepping(Box::new(
+        CogWheel::from_pattern("1101").expect("Invalid pattern"),
+    ));
+    e.encrypt(&message[..321])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(e.get_state().err(), Some(EnigmaError::UnsavedStepping));
+
+    e.set_stepping(Box::new(Typex));
+    assert_eq!(e.get_state().err(), Some(EnigmaError::UnsavedStepping));
+}
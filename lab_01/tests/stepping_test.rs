@@ -0,0 +1,84 @@
+use enigma::{
+    Enigma,
+    preset::Model,
+    stepping::{CogWheel, Odometer, Typex},
+};
+
+fn preset(positions: &str) -> Enigma<char> {
+    Enigma::from_preset(Model::M3, "B", &["I", "II", "III"], "AAA", positions, "")
+        .expect("Invalid preset")
+}
+
+// Позиции слева направо после каждого нажатия
+fn windows(e: &mut Enigma<char>, presses: usize) -> Vec<String> {
+    (0..presses)
+        .map(|_| {
+            e.encrypt(&['A']).expect("Symbol in alphabet not founded");
+            e.positions().iter().rev().collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_odometer_without_double_step() {
+    let mut e = preset("ADU");
+    assert_eq!(windows(&mut e, 3), ["ADV", "AEW", "BFX"]);
+
+    let mut e = preset("ADU");
+    e.set_stepping(Box::new(Odometer));
+    assert_eq!(windows(&mut e, 3), ["ADV", "AEW", "AEX"]);
+}
+
+#[test]
+fn test_typex_stationary_rotors() {
+    let alphabet: Vec<char> = ('A'..='Z').collect();
+    let mut e = Enigma::from_seed(&alphabet, 5, false, 7).expect("Incorrect alphabet");
+    e.set_stepping(Box::new(Typex));
+    let start = e.positions();
+
+    // Два ротора со стороны входа - статоры, быстрый ротор стоит за ними
+    let msg = vec!['A'; 26 * 26 * 26 + 10];
+    let crypto = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    assert_eq!(e.positions()[..2], start[..2]);
+    assert_ne!(e.positions()[2..], start[2..]);
+    let fast = alphabet.iter().position(|&c| c == start[2]).unwrap_or(0);
+    assert_eq!(e.positions()[2], alphabet[(fast + msg.len()) % 26]);
+
+    let mut clone = e.clone();
+    clone.seek(msg.len());
+    assert_eq!(clone.positions(), e.positions());
+
+    e.reset();
+    assert_eq!(
+        e.decrypt(&crypto).expect("Symbol in alphabet not founded"),
+        msg
+    );
+}
+
+#[test]
+fn test_cog_wheel_irregular_stepping() {
+    let mut e = preset("AAA");
+    e.set_stepping(Box::new(
+        CogWheel::from_pattern("1101").expect("Invalid pattern"),
+    ));
+    assert_eq!(windows(&mut e, 5), ["AAB", "AAC", "AAC", "AAD", "AAE"]);
+
+    // Перемотка и многопоточное шифрование учитывают состояние колеса
+    let msg: Vec<char> = ('A'..='Z').cycle().take(20_000).collect();
+    e.reset();
+    let expected = e.encrypt(&msg).expect("Symbol in alphabet not founded");
+    let end = e.positions();
+
+    e.seek(777);
+    let tail = e
+        .encrypt(&msg[777..])
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(tail, expected[777..]);
+
+    e.reset();
+    let parallel = e
+        .encrypt_parallel(&msg, 4)
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(parallel, expected);
+    assert_eq!(e.positions(), end);
+}
@@ -0,0 +1,60 @@
+use std::io::{self, Cursor, Read, Write};
+
+use enigma::{Enigma, EnigmaError, preset::Model};
+
+mod common;
+
+#[test]
+fn test_reader_matches_encrypt() {
+    let mut e = common::byte_enigma(3, true);
+    let data = common::byte_message();
+    let expected = e.encrypt(&data).expect("Symbol in alphabet not founded");
+    e.reset();
+
+    let mut crypto = Vec::new();
+    e.reader(Cursor::new(&data))
+        .read_to_end(&mut crypto)
+        .expect("Stream encryption failed");
+
+    assert_eq!(crypto, expected);
+}
+
+#[test]
+fn test_writer_matches_encrypt() {
+    let mut e = common::byte_enigma(3, true);
+    let data = common::byte_message();
+    let expected = e.encrypt(&data).expect("Symbol in alphabet not founded");
+    e.reset();
+
+    let mut crypto = Vec::new();
+    {
+        let mut writer = e.writer(&mut crypto);
+        for chunk in data.chunks(1000) {
+            writer.write_all(chunk).expect("Stream encryption failed");
+        }
+        writer.flush().expect("Stream encryption failed");
+    }
+
+    assert_eq!(crypto, expected);
+}
+
+#[test]
+fn test_stream_error_position() {
+    let mut e =
+        Enigma::<u8>::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+            .expect("Incorrect preset");
+
+    let mut writer = e.writer(io::sink());
+    writer
+        .write_all(b"HELLO")
+        .expect("Stream encryption failed");
+    let err = writer
+        .write_all(b"WORLD!")
+        .expect_err("Symbol not in alphabet expected");
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.into_inner().map(|e| e.to_string()),
+        Some(EnigmaError::SymbolNotInAlphabet(10).to_string())
+    );
+}
@@ -0,0 +1,108 @@
+use enigma::{
+    Enigma, EnigmaError, UnknownSymbols,
+    preset::Model,
+    text::{TextAlphabet, to_groups},
+};
+
+fn preset() -> Enigma<char> {
+    Enigma::from_preset(Model::EnigmaI, "B", &["II", "IV", "V"], "BUL", "BLA", "")
+        .expect("Invalid preset")
+}
+
+#[test]
+fn test_text_roundtrip() {
+    let mut e = preset();
+    let crypto = e
+        .encrypt_text("Attack at dawn\nhold the bridge")
+        .expect("Symbol in alphabet not founded");
+
+    let groups: Vec<&str> = crypto.split(' ').collect();
+    assert_eq!(groups.len(), 5);
+    assert!(groups.iter().all(|g| g.len() == 5));
+
+    e.reset();
+    assert_eq!(
+        e.decrypt_text(&crypto)
+            .expect("Symbol in alphabet not founded"),
+        "ATTACKATDAWNHOLDTHEBRIDGE"
+    );
+
+    e.reset();
+    assert_eq!(
+        e.encrypt_text("Attack at dawn!").err(),
+        Some(EnigmaError::SymbolNotInAlphabet(12))
+    );
+    e.reset();
+    e.set_unknown_symbols(UnknownSymbols::Drop);
+    assert_eq!(
+        e.encrypt_text("Attack at dawn!")
+            .expect("Symbol in alphabet not founded"),
+        &crypto[..14]
+    );
+}
+
+#[test]
+fn test_russian_alphabets() {
+    let text = "Ёлка в лесу";
+
+    let mut e = Enigma::from_alphabet(&TextAlphabet::Russian.symbols(), 3, true)
+        .expect("Incorrect alphabet");
+    assert_eq!(
+        e.normalize_text(text).iter().collect::<String>(),
+        "ЕЛКАВЛЕСУ"
+    );
+    let crypto = e
+        .encrypt_text(text)
+        .expect("Symbol in alphabet not founded");
+    e.reset();
+    assert_eq!(
+        e.decrypt_text(&crypto)
+            .expect("Symbol in alphabet not founded"),
+        "ЕЛКАВЛЕСУ"
+    );
+
+    let mut e = Enigma::from_alphabet(&TextAlphabet::RussianYo.symbols(), 3, false)
+        .expect("Incorrect alphabet");
+    let crypto = e
+        .encrypt_text(text)
+        .expect("Symbol in alphabet not founded");
+    e.reset();
+    assert_eq!(
+        e.decrypt_text(&crypto)
+            .expect("Symbol in alphabet not founded"),
+        "ЁЛКАВЛЕСУ"
+    );
+
+    assert_eq!(TextAlphabet::Russian.symbols().len(), 32);
+    assert_eq!(TextAlphabet::RussianYo.symbols().len(), 33);
+}
+
+#[test]
+fn test_groups() {
+    let symbols: Vec<char> = ('A'..='Z').cycle().take(57).collect();
+    let text = to_groups(&symbols);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[0],
+        "ABCDE FGHIJ KLMNO PQRST UVWXY ZABCD EFGHI JKLMN OPQRS TUVWX"
+    );
+    assert_eq!(lines[1], "YZABC DE");
+
+    let mut e = Enigma::from_alphabet(
+        &TextAlphabet::Custom("0123456789".to_string()).symbols(),
+        2,
+        false,
+    )
+    .expect("Incorrect alphabet");
+    let crypto = e
+        .encrypt_text("31 41 59 26 53")
+        .expect("Symbol in alphabet not founded");
+    e.reset();
+    assert_eq!(
+        e.decrypt_text(&crypto)
+            .expect("Symbol in alphabet not founded"),
+        "3141592653"
+    );
+}
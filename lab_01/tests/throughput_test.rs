@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use enigma::{
+    Enigma, Symbol,
+    cfg::{EnigmaConfig, RotorConfig},
+};
+
+const MSG_LEN: usize = 100_000;
+
+// Лучшее время из нескольких запусков, чтобы сгладить шум планировщика
+fn best_time<T: Symbol>(alphabet: &[T]) -> Duration {
+    let mut e = Enigma::from_alphabet(alphabet, 3, true).expect("Incorrect alphabet");
+    let msg: Vec<T> = alphabet.iter().cycle().take(MSG_LEN).cloned().collect();
+
+    (0..3)
+        .map(|_| {
+            e.reset();
+            let start = Instant::now();
+            e.encrypt(&msg).expect("Symbol in alphabet not founded");
+            start.elapsed()
+        })
+        .min()
+        .expect("No runs")
+}
+
+fn report(name: &str, small: Duration, large: Duration) -> f64 {
+    let throughput = |d: Duration| MSG_LEN as f64 / d.as_secs_f64();
+    println!(
+        "{}: {:.0} symb/s (small alphabet), {:.0} symb/s (large alphabet)",
+        name,
+        throughput(small),
+        throughput(large)
+    );
+
+    large.as_secs_f64() / small.as_secs_f64()
+}
+
+fn to_chars(symbols: &[u8]) -> Vec<char> {
+    symbols.iter().map(|&b| char::from(b)).collect()
+}
+
+// Байты и символы U+0000-U+00FF упорядочены одинаково, поэтому машина
+// с такой конфигурацией та же самая, но ищет символы общим путем
+fn char_config(config: &EnigmaConfig<u8>) -> EnigmaConfig<char> {
+    EnigmaConfig {
+        alphabet: to_chars(&config.alphabet),
+        commutator: config.commutator.as_deref().map(to_chars),
+        entry: config.entry.as_deref().map(to_chars),
+        reflector: to_chars(&config.reflector),
+        rotors: config
+            .rotors
+            .iter()
+            .map(|rotor| RotorConfig {
+                wiring: to_chars(&rotor.wiring),
+                notches: to_chars(&rotor.notches),
+                ring: char::from(rotor.ring),
+                position: char::from(rotor.position),
+                stationary: rotor.stationary,
+            })
+            .collect(),
+        mechanics: config.mechanics,
+    }
+}
+
+#[test]
+fn test_byte_fast_path_matches_generic_path() {
+    let msg: Vec<u8> = (0..MSG_LEN).map(|i| (i * 31 % 256) as u8).collect();
+
+    for rotors_cnt in [0, 3, 5] {
+        let mut bytes = Enigma::from_alphabet(&(0..=255).collect::<Vec<u8>>(), rotors_cnt, true)
+            .expect("Incorrect alphabet");
+        let mut chars =
+            Enigma::from_config(&char_config(&bytes.get_config())).expect("Incorrect config");
+
+        let expected = chars
+            .encrypt(&to_chars(&msg))
+            .expect("Symbol in alphabet not founded");
+        let crypto = bytes.encrypt(&msg).expect("Symbol in alphabet not founded");
+        assert_eq!(to_chars(&crypto), expected);
+    }
+}
+
+// Замеры времени зависят от загрузки машины, поэтому запускаются вручную:
+// cargo test --release -- --ignored --nocapture
+#[test]
+#[ignore]
+fn test_byte_throughput_independent_of_alphabet() {
+    let small: Vec<u8> = (b'A'..=b'Z').collect();
+    let large: Vec<u8> = (0..=255).collect();
+
+    let ratio = report("u8", best_time(&small), best_time(&large));
+    assert!(ratio < 3.0, "256-symbol alphabet is {:.1}x slower", ratio);
+}
+
+#[test]
+#[ignore]
+fn test_char_throughput_independent_of_alphabet() {
+    let small: Vec<char> = ('A'..='Z').collect();
+    let large: Vec<char> = ('\u{400}'..'\u{1400}').collect();
+
+    let ratio = report("char", best_time(&small), best_time(&large));
+    assert!(ratio < 3.0, "4096-symbol alphabet is {:.1}x slower", ratio);
+}
@@ -0,0 +1,89 @@
+use enigma::{Enigma, EnigmaError, Plugboard, UnknownSymbols, preset::Model};
+
+fn preset() -> Enigma<char> {
+    let mut e = Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "ADU", "")
+        .expect("Invalid preset");
+    e.set_plugboard(Some(
+        Plugboard::from_notation("HX EQ").expect("Invalid plugboard"),
+    ))
+    .expect("Invalid plugboard");
+    e
+}
+
+#[test]
+fn test_trace_matches_encrypt() {
+    let message: Vec<char> = "WETTERBERICHTFUERHEUTE".chars().collect();
+    let expected = preset()
+        .encrypt(&message)
+        .expect("Symbol in alphabet not founded");
+
+    let mut e = preset();
+    let (crypto, traces) = e
+        .encrypt_traced(&message)
+        .expect("Symbol in alphabet not founded");
+
+    assert_eq!(crypto, expected);
+    assert_eq!(traces.len(), message.len());
+    for (i, trace) in traces.iter().enumerate() {
+        assert_eq!(trace.index, i);
+        assert_eq!(trace.input, message[i]);
+        assert_eq!(trace.output, expected[i]);
+        assert_eq!(trace.forward.len(), 3);
+        assert_eq!(trace.backward.len(), 3);
+    }
+    assert_eq!(e.positions(), preset_after(&message));
+}
+
+fn preset_after(message: &[char]) -> Vec<char> {
+    let mut e = preset();
+    e.encrypt(message).expect("Symbol in alphabet not founded");
+    e.positions()
+}
+
+#[test]
+fn test_trace_path() {
+    let mut e = preset();
+    let (_, traces) = e
+        .encrypt_traced(&['H', 'E', 'L'])
+        .expect("Symbol in alphabet not founded");
+
+    // Позиции в порядке `positions`: от быстрого ротора к медленному,
+    // третий символ вызывает двойной шаг среднего ротора
+    assert_eq!(traces[0].positions_before, ['U', 'D', 'A']);
+    assert_eq!(traces[0].positions_after, ['V', 'D', 'A']);
+    assert_eq!(traces[1].positions_after, ['W', 'E', 'A']);
+    assert_eq!(traces[2].positions_after, ['X', 'F', 'B']);
+
+    // Коммутатор меняет H на X на входе
+    assert_eq!(traces[0].plugboard_in, 'X');
+    assert_eq!(traces[1].plugboard_in, 'Q');
+    assert_eq!(traces[2].plugboard_in, 'L');
+
+    // Рефлектор не оставляет символ на месте
+    for trace in &traces {
+        assert_ne!(trace.forward[2], trace.reflector);
+    }
+}
+
+#[test]
+fn test_trace_unknown_symbols() {
+    let message: Vec<char> = "AB C".chars().collect();
+
+    let mut e = preset();
+    assert_eq!(
+        e.encrypt_traced(&message).err(),
+        Some(EnigmaError::SymbolNotInAlphabet(2))
+    );
+
+    let mut e = preset();
+    e.set_unknown_symbols(UnknownSymbols::PassThrough);
+    let (crypto, traces) = e
+        .encrypt_traced(&message)
+        .expect("Symbol in alphabet not founded");
+
+    assert_eq!(crypto[2], ' ');
+    assert_eq!(
+        traces.iter().map(|t| t.index).collect::<Vec<_>>(),
+        [0, 1, 3]
+    );
+}
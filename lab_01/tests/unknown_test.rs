@@ -0,0 +1,109 @@
+use std::io::{Cursor, Read, Write};
+
+use enigma::{Enigma, EnigmaError, UnknownSymbols, preset::Model};
+
+const MESSAGE: &str = "ATTACK AT DAWN, 05:30! HOLD THE BRIDGE.";
+
+fn preset() -> Enigma<char> {
+    Enigma::from_preset(Model::EnigmaI, "B", &["I", "II", "III"], "AAA", "AAA", "")
+        .expect("Invalid preset")
+}
+
+fn letters(text: &str) -> Vec<char> {
+    text.chars().filter(char::is_ascii_uppercase).collect()
+}
+
+#[test]
+fn test_pass_through() {
+    let message: Vec<char> = MESSAGE.chars().collect();
+
+    let mut e = preset();
+    assert_eq!(
+        e.encrypt(&message).err(),
+        Some(EnigmaError::SymbolNotInAlphabet(6))
+    );
+
+    e.reset();
+    e.set_unknown_symbols(UnknownSymbols::PassThrough);
+    let crypto = e.encrypt(&message).expect("Symbol in alphabet not founded");
+
+    // Символы не из алфавита остаются на местах и не вращают роторы
+    for (plain, symb) in message.iter().zip(&crypto) {
+        assert_eq!(plain.is_ascii_uppercase(), symb.is_ascii_uppercase());
+        if !plain.is_ascii_uppercase() {
+            assert_eq!(plain, symb);
+        }
+    }
+    let mut plain = preset();
+    assert_eq!(
+        letters(&crypto.iter().collect::<String>()),
+        plain
+            .encrypt(&letters(MESSAGE))
+            .expect("Symbol in alphabet not founded")
+    );
+
+    e.reset();
+    assert_eq!(
+        e.decrypt(&crypto).expect("Symbol in alphabet not founded"),
+        message
+    );
+}
+
+#[test]
+fn test_drop() {
+    let mut message: Vec<char> = MESSAGE.chars().collect();
+
+    let mut e = preset();
+    e.set_unknown_symbols(UnknownSymbols::Drop);
+    let crypto = e.encrypt(&message).expect("Symbol in alphabet not founded");
+
+    let expected = preset()
+        .encrypt(&letters(MESSAGE))
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(crypto, expected);
+
+    // На месте выброшенные символы сдвигают результат к началу буфера
+    e.reset();
+    let len = e
+        .encrypt_in_place(&mut message)
+        .expect("Symbol in alphabet not founded");
+    assert_eq!(message[..len], expected);
+}
+
+#[test]
+fn test_parallel_and_stream() {
+    let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7 % 96) as u8 + 32).collect();
+
+    for unknown in [UnknownSymbols::PassThrough, UnknownSymbols::Drop] {
+        let mut e =
+            Enigma::<u8>::from_preset(Model::M3, "C", &["VI", "II", "VIII"], "QWE", "RTY", "")
+                .expect("Invalid preset");
+        e.set_unknown_symbols(unknown);
+        let expected = e.encrypt(&data).expect("Symbol in alphabet not founded");
+        let positions = e.positions();
+
+        e.reset();
+        let crypto = e
+            .encrypt_parallel(&data, 7)
+            .expect("Symbol in alphabet not founded");
+        assert_eq!(crypto, expected);
+        assert_eq!(e.positions(), positions);
+
+        e.reset();
+        let mut crypto = Vec::new();
+        e.reader(Cursor::new(&data))
+            .read_to_end(&mut crypto)
+            .expect("Stream encryption failed");
+        assert_eq!(crypto, expected);
+
+        e.reset();
+        let mut crypto = Vec::new();
+        {
+            let mut writer = e.writer(&mut crypto);
+            for chunk in data.chunks(999) {
+                writer.write_all(chunk).expect("Stream encryption failed");
+            }
+        }
+        assert_eq!(crypto, expected);
+    }
+}